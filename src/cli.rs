@@ -1,37 +1,339 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use snapctl::operations::stream::LoopStatus;
+use snapctl::rpc::events::EventKind;
+use snapctl::rpc::transport::Transport;
+use crate::utils::jsonpath::JsonPath;
+use crate::utils::output::OutputFormat;
 
 /// Command-line interface for the application.
 /// Main CLI structure for the Snapcast Control Utility.
 #[derive(Parser, Debug)]
 #[clap(author, version, about = "Snapcast Control Utility", long_about = None)]
 pub struct Cli {
-    /// Host address for the Snapcast server.
+    /// Full server URL, e.g. ws://192.168.1.10:1780/jsonrpc or wss://user:pass@[::1]:443/snapcast/jsonrpc.
+    /// Without a port, ws, wss, http and https use the standard port of the scheme (80 or 443), not snapserver's 1780,
+    /// and tcp uses 1705. Takes precedence over --host, --port, --transport and --tls.
+    #[clap(long, global = true, env = "SNAPSERVER_URL")]
+    pub url: Option<String>,
+
+    /// Host address for the Snapcast server (IPv4, IPv6 or hostname).
     #[clap(short = 'H', long, global = true, default_value = "127.0.0.1", env = "SNAPSERVER_HOST")]
     pub host: String,
 
-    /// Port number for the Snapcast server.
-    #[clap(short, long, global = true, default_value = "1780", env = "SNAPSERVER_PORT")]
-    pub port: u16,
+    /// Port number for the Snapcast server [default: 1780 for ws and http, 1705 for tcp].
+    #[clap(short, long, global = true, env = "SNAPSERVER_PORT")]
+    pub port: Option<u16>,
+
+    /// Transport used to reach the Snapcast server. A scheme in the host (e.g. tcp://host) takes precedence.
+    #[clap(short = 't', long, global = true, value_enum, default_value = "ws", env = "SNAPSERVER_TRANSPORT")]
+    pub transport: TransportArg,
+
+    /// Use TLS (wss:// or https://) to reach the Snapcast server [default port: 1788].
+    #[clap(long, global = true, env = "SNAPSERVER_TLS")]
+    pub tls: bool,
+
+    /// PEM file with additional CA certificates to trust.
+    #[clap(long, global = true, value_name = "FILE", env = "SNAPSERVER_CA_CERT")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// PEM client certificate presented to the server.
+    #[clap(long, global = true, value_name = "FILE", env = "SNAPSERVER_CLIENT_CERT", requires = "client_key")]
+    pub client_cert: Option<PathBuf>,
+
+    /// PEM private key for the client certificate.
+    #[clap(long, global = true, value_name = "FILE", env = "SNAPSERVER_CLIENT_KEY", requires = "client_cert")]
+    pub client_key: Option<PathBuf>,
+
+    /// Skip verification of the server certificate. Insecure, for testing only.
+    #[clap(long, global = true, env = "SNAPSERVER_INSECURE")]
+    pub insecure: bool,
+
+    /// Username for servers or proxies that require Basic authentication.
+    #[clap(short = 'u', long, global = true, env = "SNAPSERVER_USERNAME", conflicts_with_all = ["token", "token_file", "token_command"])]
+    pub username: Option<String>,
+
+    /// Password for Basic authentication.
+    #[clap(long, global = true, env = "SNAPSERVER_PASSWORD", hide_env_values = true, requires = "username", conflicts_with_all = ["password_file", "password_command"])]
+    pub password: Option<String>,
+
+    /// Read the password from the first line of a file.
+    #[clap(long, global = true, value_name = "FILE", env = "SNAPSERVER_PASSWORD_FILE", requires = "username", conflicts_with = "password_command")]
+    pub password_file: Option<PathBuf>,
+
+    /// Read the password from the output of a shell command.
+    #[clap(long, global = true, value_name = "COMMAND", env = "SNAPSERVER_PASSWORD_COMMAND", requires = "username")]
+    pub password_command: Option<String>,
+
+    /// Bearer token for servers or proxies that require token authentication.
+    #[clap(long, global = true, env = "SNAPSERVER_TOKEN", hide_env_values = true, conflicts_with_all = ["token_file", "token_command"])]
+    pub token: Option<String>,
+
+    /// Read the bearer token from the first line of a file.
+    #[clap(long, global = true, value_name = "FILE", env = "SNAPSERVER_TOKEN_FILE", conflicts_with = "token_command")]
+    pub token_file: Option<PathBuf>,
+
+    /// Read the bearer token from the output of a shell command.
+    #[clap(long, global = true, value_name = "COMMAND", env = "SNAPSERVER_TOKEN_COMMAND")]
+    pub token_command: Option<String>,
+
+    /// Time allowed to connect to the server, e.g. 10s or 500ms (0 waits indefinitely).
+    #[clap(long, global = true, value_name = "DURATION", default_value = "10s", value_parser = parse_duration, env = "SNAPSERVER_CONNECT_TIMEOUT")]
+    pub connect_timeout: Duration,
+
+    /// Time allowed for each response from the server (0 waits indefinitely).
+    #[clap(long, global = true, value_name = "DURATION", default_value = "30s", value_parser = parse_duration, env = "SNAPSERVER_REQUEST_TIMEOUT")]
+    pub request_timeout: Duration,
+
+    /// Number of times a connection that timed out or was refused, or a status read that timed out or failed to reach the server (e.g. a refused or non-2xx HTTP POST), is retried.
+    #[clap(long, global = true, default_value_t = 2, env = "SNAPSERVER_RETRIES")]
+    pub retries: u32,
+
+    /// Delay before the first retry, doubled after every further attempt.
+    #[clap(long, global = true, value_name = "DURATION", default_value = "500ms", value_parser = parse_duration, env = "SNAPSERVER_RETRY_BACKOFF")]
+    pub retry_backoff: Duration,
+
+    /// Output format of get, set and delete commands: table, wide, json, yaml, csv,
+    /// custom-columns=HEADER:PATH,..., jsonpath=TEMPLATE or template=TEMPLATE. json and yaml print the full objects.
+    #[clap(short = 'o', long, global = true, value_name = "FORMAT", default_value = "table", value_parser = parse_output_format, env = "SNAPCTL_OUTPUT")]
+    pub output: OutputFormat,
+
+    /// Sort lists by the value at a JSONPath, e.g. .config.volume.percent.
+    #[clap(long, global = true, value_name = "PATH", value_parser = parse_json_path)]
+    pub sort_by: Option<JsonPath>,
+
+    /// Leave out the header line of table, wide, csv and custom-columns output.
+    #[clap(long, global = true)]
+    pub no_headers: bool,
+
+    /// Color status values and changes: auto colors only when printing to a terminal and NO_COLOR is unset.
+    #[clap(long, global = true, value_enum, value_name = "WHEN", default_value = "auto", env = "SNAPCTL_COLOR")]
+    pub color: ColorMode,
 
     /// Command to execute.
     #[clap(subcommand)]
     pub command: Command,
 }
 
+/// When to use colors in tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Values of `--transport`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TransportArg {
+    /// JSON-RPC over WebSocket.
+    Ws,
+    /// Newline-delimited JSON-RPC over raw TCP.
+    Tcp,
+    /// One HTTP POST to /jsonrpc per request, without notifications.
+    Http,
+}
+
+impl From<TransportArg> for Transport {
+    fn from(transport: TransportArg) -> Self {
+        match transport {
+            TransportArg::Ws => Transport::Ws,
+            TransportArg::Tcp => Transport::Tcp,
+            TransportArg::Http => Transport::Http,
+        }
+    }
+}
+
+/// Parses a duration such as `1.5s`, `500ms` or `2m`. A bare number is in seconds.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: f64 = amount.parse().map_err(|_| format!("invalid duration '{}'", value))?;
+    let seconds = match unit.trim() {
+        "ms" => amount / 1000.0,
+        "" | "s" => amount,
+        "m" => amount * 60.0,
+        _ => return Err(format!("invalid duration unit in '{}', use ms, s or m", value)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("invalid duration '{}': {}", value, e))
+}
+
+fn parse_output_format(value: &str) -> Result<OutputFormat, String> {
+    OutputFormat::parse(value).map_err(|e| e.to_string())
+}
+
+fn parse_json_path(value: &str) -> Result<JsonPath, String> {
+    JsonPath::parse(value).map_err(|e| e.to_string())
+}
+
 /// Commands available in the application.
 /// Enum representing the available commands.
 #[derive(Subcommand, Debug)]
 pub enum Command {
     Get(GetArgs),
     Set(SetArgs),
+    Create(CreateArgs),
     Delete(DeleteArgs),
+    /// Control playback of a stream, addressed directly or through a group or client.
+    Stream(StreamControlArgs),
+    /// Print server notifications as they arrive, reconnecting after connection drops.
+    Watch(WatchArgs),
     Version,
 }
 
+/// Arguments for the 'stream' command.
+#[derive(Parser, Debug)]
+pub struct StreamControlArgs {
+    /// Playback command to send.
+    #[clap(subcommand)]
+    pub command: StreamControlCommand,
+}
+
+/// Playback commands of the 'stream' command, sent with Stream.Control.
+#[derive(Subcommand, Debug)]
+pub enum StreamControlCommand {
+    /// Start playback.
+    Play(StreamTargetArgs),
+    /// Pause playback.
+    Pause(StreamTargetArgs),
+    /// Toggle between playing and paused.
+    #[clap(name = "playpause")]
+    PlayPause(StreamTargetArgs),
+    /// Stop playback.
+    Stop(StreamTargetArgs),
+    /// Skip to the next track.
+    Next(StreamTargetArgs),
+    /// Go back to the previous track.
+    Previous(StreamTargetArgs),
+    /// Move the playback position forwards or backwards.
+    Seek {
+        #[clap(flatten)]
+        target: StreamTargetArgs,
+
+        /// Offset to move by, e.g. 30s, or -10s to go back.
+        #[clap(long, allow_hyphen_values = true, value_parser = parse_offset)]
+        offset: f64,
+    },
+    /// Jump to a position in the current track.
+    SetPosition {
+        #[clap(flatten)]
+        target: StreamTargetArgs,
+
+        /// Position from the start of the track, e.g. 90s or 1.5m.
+        #[clap(long, value_parser = parse_duration)]
+        position: Duration,
+    },
+}
+
+/// The stream a command applies to: given by ID, or the one a group or client is playing.
+#[derive(Parser, Debug)]
+#[clap(group(ArgGroup::new("target").required(true).args(["stream_id", "group", "client"])))]
+pub struct StreamTargetArgs {
+    /// ID of the stream.
+    pub stream_id: Option<String>,
+
+    /// Use the stream this group, given by ID or name, is playing.
+    #[clap(long)]
+    pub group: Option<String>,
+
+    /// Use the stream the group of this client is playing.
+    #[clap(long)]
+    pub client: Option<String>,
+}
+
+/// Parses a signed offset such as `30s` or `-10s` into seconds.
+fn parse_offset(value: &str) -> Result<f64, String> {
+    let value = value.trim();
+    match value.strip_prefix('-') {
+        Some(magnitude) => parse_duration(magnitude).map(|offset| -offset.as_secs_f64()),
+        None => parse_duration(value.trim_start_matches('+')).map(|offset| offset.as_secs_f64()),
+    }
+}
+
+/// Arguments for the 'watch' command.
+#[derive(Parser, Debug, Clone)]
+pub struct WatchArgs {
+    /// Print human-readable lines or one JSON object per line.
+    #[clap(long, value_enum, default_value = "text")]
+    pub format: WatchFormat,
+
+    /// Only print these event types (repeatable or comma-separated).
+    #[clap(long = "event", value_enum, value_delimiter = ',', value_name = "EVENT")]
+    pub events: Vec<EventKindArg>,
+
+    /// Only print events about these clients, by ID or name.
+    #[clap(long = "client", value_delimiter = ',', value_name = "CLIENT")]
+    pub clients: Vec<String>,
+
+    /// Only print events about these groups, by ID or name.
+    #[clap(long = "group", value_delimiter = ',', value_name = "GROUP")]
+    pub groups: Vec<String>,
+
+    /// Only print events about these streams.
+    #[clap(long = "stream", value_delimiter = ',', value_name = "STREAM")]
+    pub streams: Vec<String>,
+}
+
+/// Output format of the 'watch' command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WatchFormat {
+    /// Human-readable lines.
+    Text,
+    /// JSON Lines, one notification object per line.
+    Json,
+}
+
+/// Values of `watch --event`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EventKindArg {
+    ClientConnect,
+    ClientDisconnect,
+    ClientVolume,
+    ClientLatency,
+    ClientName,
+    GroupMute,
+    GroupStream,
+    GroupName,
+    StreamUpdate,
+    StreamProperties,
+    ServerUpdate,
+    /// Notifications not covered by the other kinds.
+    Other,
+}
+
+impl From<EventKindArg> for EventKind {
+    fn from(kind: EventKindArg) -> Self {
+        match kind {
+            EventKindArg::ClientConnect => EventKind::ClientConnect,
+            EventKindArg::ClientDisconnect => EventKind::ClientDisconnect,
+            EventKindArg::ClientVolume => EventKind::ClientVolume,
+            EventKindArg::ClientLatency => EventKind::ClientLatency,
+            EventKindArg::ClientName => EventKind::ClientName,
+            EventKindArg::GroupMute => EventKind::GroupMute,
+            EventKindArg::GroupStream => EventKind::GroupStream,
+            EventKindArg::GroupName => EventKind::GroupName,
+            EventKindArg::StreamUpdate => EventKind::StreamUpdate,
+            EventKindArg::StreamProperties => EventKind::StreamProperties,
+            EventKindArg::ServerUpdate => EventKind::ServerUpdate,
+            EventKindArg::Other => EventKind::Other,
+        }
+    }
+}
+
 
 /// Arguments for the 'get' command.
 #[derive(Parser, Debug)]
 pub struct GetArgs {
+    /// Keep the table on screen and redraw it whenever the server reports a change.
+    #[clap(short, long, global = true)]
+    pub watch: bool,
+
+    /// Time between redraws when no notifications arrive, e.g. with the http transport.
+    #[clap(long, global = true, value_name = "DURATION", default_value = "5s", value_parser = parse_duration, requires = "watch")]
+    pub interval: Duration,
+
     /// Subcommand for the get command.
     #[clap(subcommand)]
     pub subcommand: GetSubcommand,
@@ -41,9 +343,16 @@ pub struct GetArgs {
 /// Enum representing the available subcommands for the 'get' command.
 #[derive(Subcommand, Debug)]
 pub enum GetSubcommand {
-    
+    /// Server host, software versions and object counts.
+    Server,
     Streams,
-    Stream { stream_id: String },
+    Stream {
+        stream_id: String,
+
+        /// Save the cover art of the current track to this file, rewritten on track changes with --watch.
+        #[clap(long, value_name = "FILE")]
+        artwork: Option<PathBuf>,
+    },
     Groups,
     Group { identifier: String },
     Clients,
@@ -97,8 +406,282 @@ pub enum SetSubcommand {
         #[clap(long)]
         clients: Option<String>,
     },
+
+    /// Change the player properties of a controllable stream (requires snapserver 0.26 or later).
+    Stream {
+
+        stream_id: String,
+
+        /// Repeat mode.
+        #[clap(long, value_enum)]
+        loop_status: Option<LoopStatusArg>,
+
+        #[clap(long)]
+        shuffle: Option<bool>,
+
+        /// Player volume in percent (0-100).
+        #[clap(long)]
+        volume: Option<i64>,
+
+        #[clap(long)]
+        mute: Option<bool>,
+
+        /// Playback speed, 1.0 being normal.
+        #[clap(long)]
+        rate: Option<f64>,
+    },
+}
+
+
+/// Arguments for the 'create' command.
+#[derive(Parser, Debug)]
+pub struct CreateArgs {
+    /// Subcommand for the create command.
+    #[clap(subcommand)]
+    pub subcommand: CreateSubcommand,
+}
+
+/// Enum representing the available subcommands for the 'create' command.
+#[derive(Subcommand, Debug)]
+pub enum CreateSubcommand {
+    /// Add a stream to the server (requires snapserver 0.16 or later).
+    Stream {
+        #[clap(subcommand)]
+        source: StreamSourceArgs,
+    },
 }
 
+/// Source of a new stream, one subcommand per snapserver stream type.
+#[derive(Subcommand, Debug)]
+pub enum StreamSourceArgs {
+    /// Read PCM audio from a named pipe.
+    Pipe {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Path of the pipe.
+        #[clap(long, default_value = "/tmp/snapfifo")]
+        path: String,
+
+        /// Create the pipe if it does not exist, or only read an existing one.
+        #[clap(long, value_enum)]
+        mode: Option<PipeMode>,
+    },
+
+    /// Play Spotify with librespot.
+    Librespot {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Path of the librespot binary.
+        #[clap(long, default_value = "/usr/bin/librespot")]
+        path: String,
+
+        /// Name shown in Spotify Connect.
+        #[clap(long)]
+        devicename: Option<String>,
+
+        /// Bitrate in kbit/s.
+        #[clap(long, value_parser = ["96", "160", "320"])]
+        bitrate: Option<String>,
+
+        /// Spotify account username.
+        #[clap(long)]
+        spotify_username: Option<String>,
+
+        /// Spotify account password.
+        #[clap(long, requires = "spotify_username")]
+        spotify_password: Option<String>,
+
+        /// Initial volume in percent.
+        #[clap(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        volume: Option<u8>,
+
+        /// Normalize the volume of tracks.
+        #[clap(long)]
+        normalize: bool,
+
+        /// Keep playing similar songs after the queue ends.
+        #[clap(long)]
+        autoplay: bool,
+
+        /// Directory librespot caches credentials and audio in.
+        #[clap(long)]
+        cache: Option<String>,
+
+        /// Additional command-line arguments for librespot.
+        #[clap(long)]
+        args: Option<String>,
+    },
+
+    /// Receive AirPlay audio with shairport-sync.
+    Airplay {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Path of the shairport-sync binary.
+        #[clap(long, default_value = "/usr/bin/shairport-sync")]
+        path: String,
+
+        /// Name shown to AirPlay senders.
+        #[clap(long)]
+        devicename: Option<String>,
+
+        /// Port shairport-sync listens on.
+        #[clap(long)]
+        airplay_port: Option<u16>,
+
+        /// Password AirPlay senders must provide.
+        #[clap(long)]
+        airplay_password: Option<String>,
+    },
+
+    /// Read PCM audio from a TCP connection.
+    Tcp {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Address to listen on in server mode, or to connect to in client mode.
+        #[clap(long, value_name = "HOST:PORT", default_value = "0.0.0.0:4953")]
+        address: String,
+
+        /// Wait for connections (server) or connect to the address (client).
+        #[clap(long, value_enum)]
+        mode: Option<TcpMode>,
+    },
+
+    /// Play a file of raw PCM audio.
+    File {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Path of the PCM file.
+        #[clap(long)]
+        path: String,
+    },
+
+    /// Read PCM audio from the output of a process.
+    Process {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Path of the executable.
+        #[clap(long)]
+        path: String,
+
+        /// Command-line arguments for the process.
+        #[clap(long)]
+        args: Option<String>,
+
+        /// Restart the process after this many seconds without output (0 disables the watchdog).
+        #[clap(long, value_name = "SECONDS")]
+        wd_timeout: Option<u64>,
+
+        /// Log what the process writes to stderr.
+        #[clap(long)]
+        log_stderr: bool,
+    },
+
+    /// Capture audio from an ALSA device.
+    Alsa {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// ALSA capture device, e.g. hw:0,0.
+        #[clap(long, default_value = "default")]
+        device: String,
+
+        /// Keep sending audio while the device is silent.
+        #[clap(long)]
+        send_silence: bool,
+
+        /// Milliseconds of silence before the stream turns idle.
+        #[clap(long, value_name = "MS")]
+        idle_threshold: Option<u64>,
+
+        /// Level in percent under which audio counts as silence.
+        #[clap(long, value_name = "PERCENT")]
+        silence_threshold_percent: Option<f64>,
+    },
+
+    /// Play the first active stream out of several.
+    Meta {
+        #[clap(flatten)]
+        stream: NewStreamArgs,
+
+        /// Streams to choose from, highest priority first (repeatable or comma-separated).
+        #[clap(long = "source", value_delimiter = ',', value_name = "STREAM", required = true)]
+        sources: Vec<String>,
+    },
+}
+
+/// Settings shared by every stream type.
+#[derive(Parser, Debug)]
+pub struct NewStreamArgs {
+    /// Name of the stream, which also becomes its ID.
+    pub name: String,
+
+    /// Sample format as rate:bits:channels, e.g. 48000:16:2.
+    #[clap(long)]
+    pub sampleformat: Option<String>,
+
+    /// Codec used to send the stream to clients, e.g. flac, ogg, opus or pcm.
+    #[clap(long)]
+    pub codec: Option<String>,
+
+    /// Milliseconds of audio read at a time.
+    #[clap(long, value_name = "MS")]
+    pub chunk_ms: Option<u32>,
+
+    /// Script that reports metadata and handles playback control.
+    #[clap(long)]
+    pub controlscript: Option<String>,
+
+    /// Other URI parameters, e.g. --param dryout_ms=2000 (repeatable).
+    #[clap(long = "param", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub params: Vec<(String, String)>,
+}
+
+/// Values of `set stream --loop-status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LoopStatusArg {
+    /// Play the queue once.
+    None,
+    /// Repeat the current track.
+    Track,
+    /// Repeat the whole playlist.
+    Playlist,
+}
+
+impl From<LoopStatusArg> for LoopStatus {
+    fn from(loop_status: LoopStatusArg) -> Self {
+        match loop_status {
+            LoopStatusArg::None => LoopStatus::None,
+            LoopStatusArg::Track => LoopStatus::Track,
+            LoopStatusArg::Playlist => LoopStatus::Playlist,
+        }
+    }
+}
+
+/// How a pipe stream opens its pipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PipeMode {
+    Create,
+    Read,
+}
+
+/// Whether a tcp stream listens or connects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TcpMode {
+    Server,
+    Client,
+}
+
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+    value.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("invalid parameter '{}', expected KEY=VALUE", value))
+}
 
 /// Arguments for the 'delete' command.
 #[derive(Parser, Debug)]
@@ -114,4 +697,6 @@ pub struct DeleteArgs {
 pub enum DeleteSubcommand {
     Client { client_id: String },
     Clients { client_ids: String },
+    /// Remove a stream from the server (requires snapserver 0.16 or later).
+    Stream { stream_id: String },
 }
@@ -0,0 +1 @@
+pub mod stream;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use snapctl::operations;
+use snapctl::operations::stream::StreamSource;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::cli::{NewStreamArgs, PipeMode, StreamSourceArgs, TcpMode};
+use crate::commands::get::stream::stream_output;
+use crate::utils::output::Output;
+
+/// Adds a stream to the server and shows it.
+pub async fn create_stream(client: &SnapcastRpcClient, source: StreamSourceArgs) -> Result<Output> {
+    let source = stream_source(source);
+    let details = operations::stream::add_stream(client, &source).await?;
+
+    stream_output(&details)
+}
+
+/// Builds the stream URI parameters from the options of each stream type.
+fn stream_source(source: StreamSourceArgs) -> StreamSource {
+    match source {
+        StreamSourceArgs::Pipe { stream, path, mode } => {
+            let mode = mode.map(|mode| match mode {
+                PipeMode::Create => "create",
+                PipeMode::Read => "read",
+            });
+            common(StreamSource::new("pipe", &path, &stream.name), stream)
+                .param_opt("mode", mode)
+        }
+        StreamSourceArgs::Librespot {
+            stream, path, devicename, bitrate, spotify_username, spotify_password, volume, normalize, autoplay, cache, args,
+        } => common(StreamSource::new("librespot", &path, &stream.name), stream)
+            .param_opt("devicename", devicename)
+            .param_opt("bitrate", bitrate)
+            .param_opt("username", spotify_username)
+            .param_opt("password", spotify_password)
+            .param_opt("volume", volume)
+            .param_opt("normalize", normalize.then_some(true))
+            .param_opt("autoplay", autoplay.then_some(true))
+            .param_opt("cache", cache)
+            .param_opt("params", args),
+        StreamSourceArgs::Airplay { stream, path, devicename, airplay_port, airplay_password } => {
+            common(StreamSource::new("airplay", &path, &stream.name), stream)
+                .param_opt("devicename", devicename)
+                .param_opt("port", airplay_port)
+                .param_opt("password", airplay_password)
+        }
+        StreamSourceArgs::Tcp { stream, address, mode } => {
+            let mode = mode.map(|mode| match mode {
+                TcpMode::Server => "server",
+                TcpMode::Client => "client",
+            });
+            common(StreamSource::new("tcp", &address, &stream.name), stream)
+                .param_opt("mode", mode)
+        }
+        StreamSourceArgs::File { stream, path } => {
+            common(StreamSource::new("file", &path, &stream.name), stream)
+        }
+        StreamSourceArgs::Process { stream, path, args, wd_timeout, log_stderr } => {
+            common(StreamSource::new("process", &path, &stream.name), stream)
+                .param_opt("params", args)
+                .param_opt("wd_timeout", wd_timeout)
+                .param_opt("log_stderr", log_stderr.then_some(true))
+        }
+        StreamSourceArgs::Alsa { stream, device, send_silence, idle_threshold, silence_threshold_percent } => {
+            common(StreamSource::new("alsa", "/", &stream.name), stream)
+                .param("device", device)
+                .param_opt("send_silence", send_silence.then_some(true))
+                .param_opt("idle_threshold", idle_threshold)
+                .param_opt("silence_threshold_percent", silence_threshold_percent)
+        }
+        StreamSourceArgs::Meta { stream, sources } => {
+            let location = format!("/{}", sources.join("/"));
+            common(StreamSource::new("meta", &location, &stream.name), stream)
+        }
+    }
+}
+
+/// Adds the parameters shared by every stream type.
+fn common(source: StreamSource, stream: NewStreamArgs) -> StreamSource {
+    let source = source
+        .param_opt("sampleformat", stream.sampleformat)
+        .param_opt("codec", stream.codec)
+        .param_opt("chunk_ms", stream.chunk_ms)
+        .param_opt("controlscript", stream.controlscript);
+    stream.params.into_iter().fold(source, |source, (key, value)| source.param(&key, value))
+}
@@ -1,2 +1,4 @@
 pub mod client;
 pub mod clients;
+
+pub mod stream;
\ No newline at end of file
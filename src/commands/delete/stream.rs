@@ -0,0 +1,19 @@
+use anyhow::Result;
+use snapctl::operations;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::commands::get::streams::streams_output;
+use crate::utils::output::Output;
+
+/// Removes a stream from the server, warning about the groups currently playing it.
+pub async fn delete_stream(client: &SnapcastRpcClient, stream_id: &str) -> Result<Output> {
+    let details = operations::stream::get_stream(client, stream_id).await?;
+
+    if !details.groups.is_empty() {
+        let group_ids: Vec<&str> = details.groups.iter().map(|group| group.id.as_str()).collect();
+        eprintln!("Warning: removing stream '{}' while group(s) {} are playing it", stream_id, group_ids.join(", "));
+    }
+
+    let server_info = operations::stream::remove_stream(client, stream_id).await?;
+
+    streams_output(&server_info)
+}
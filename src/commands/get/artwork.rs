@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use snapctl::models::Stream;
+use snapctl::operations;
+use snapctl::rpc::transport::ConnectionOptions;
+
+/// File that `get stream --artwork` saves the cover art of the current track to.
+///
+/// With `--watch`, the file is only rewritten when the track changes, and failing
+/// downloads are reported on stderr and retried on the next redraw instead of ending the watch.
+pub struct ArtworkFile {
+    path: PathBuf,
+    server_url: String,
+    options: ConnectionOptions,
+    watching: bool,
+    /// Metadata of the track whose artwork was last saved.
+    saved: Mutex<Option<Value>>,
+}
+
+impl ArtworkFile {
+    pub fn new(path: PathBuf, server_url: &str, options: &ConnectionOptions, watching: bool) -> Self {
+        ArtworkFile {
+            path,
+            server_url: server_url.to_string(),
+            options: options.clone(),
+            watching,
+            saved: Mutex::new(None),
+        }
+    }
+
+    /// Saves the artwork of the track `stream` plays, unless it is already in the file.
+    pub async fn update(&self, stream: &Stream) -> Result<()> {
+        let track = stream.properties.as_ref()
+            .and_then(|properties| properties.get("metadata"))
+            .cloned()
+            .unwrap_or_default();
+        if self.saved.lock().unwrap().as_ref() == Some(&track) {
+            return Ok(());
+        }
+
+        let artwork = match operations::stream::fetch_artwork(stream, &self.server_url, &self.options).await {
+            Ok(artwork) => artwork,
+            Err(e) if self.watching => {
+                eprintln!("Failed to load artwork of stream '{}': {:#}", stream.id, e);
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        match artwork {
+            Some(artwork) => {
+                write_replacing(&self.path, &artwork.data)?;
+                eprintln!(
+                    "Saved {} artwork of stream '{}' to {}",
+                    artwork.format.as_deref().unwrap_or("unknown"),
+                    stream.id,
+                    self.path.display()
+                );
+            }
+            None if self.watching => {
+                eprintln!("Stream '{}' has no artwork for the current track, keeping {}", stream.id, self.path.display());
+            }
+            None => return Err(anyhow!("Stream '{}' has no artwork for the current track", stream.id)),
+        }
+
+        *self.saved.lock().unwrap() = Some(track);
+        Ok(())
+    }
+}
+
+/// Writes to a temporary file next to `path` and renames it over `path`, so that
+/// programs reading the file never see a partial image.
+fn write_replacing(path: &Path, data: &[u8]) -> Result<()> {
+    let file_name = path.file_name()
+        .ok_or_else(|| anyhow!("Artwork path '{}' is not a file", path.display()))?;
+    let temporary = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    std::fs::write(&temporary, data)
+        .with_context(|| format!("Failed to write artwork to {}", path.display()))?;
+    std::fs::rename(&temporary, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))
+}
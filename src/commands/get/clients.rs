@@ -1,50 +1,62 @@
-use crate::rpc::client::SnapcastRpcClient;
-use crate::utils::display::print_table;
+use snapctl::operations;
+use snapctl::operations::client::ClientInfo;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::utils::display::Table;
+use crate::utils::output::Output;
 use anyhow::Result;
 
-pub async fn get_clients(server_url: &str) -> Result<()> {
-    let client = SnapcastRpcClient::new(server_url);
-    let server_info = client.get_status().await?;
+/// Message shown instead of an empty client table.
+const NO_CLIENTS: &str = "No clients found (no groups available).";
 
-    let headers = vec!["CLIENT ID", "STATUS", "GROUP ID", "STREAM ID"];
-    let mut data = Vec::new();
-
-    // Get groups array
-    let groups = if let Some(groups) = server_info.get("groups").and_then(|g| g.as_array()) {
-        groups
-    } else {
-        println!("No clients found (no groups available).");
-        return Ok(());
-    };
-
-    // Process each group to find clients
-    for group in groups {
-        let group_id = group.get("id")
-            .and_then(|id| id.as_str())
-            .unwrap_or("unknown").to_string();
-
-        let stream_id = group.get("stream_id")
-            .and_then(|id| id.as_str())
-            .unwrap_or("unknown").to_string();
-
-        if let Some(clients) = group.get("clients").and_then(|c| c.as_array()) {
-            for client in clients {
-                let client_id = client.get("id")
-                    .and_then(|id| id.as_str())
-                    .unwrap_or("unknown").to_string();
+pub async fn get_clients(client: &SnapcastRpcClient) -> Result<Output> {
+    let clients = operations::client::list_clients(client).await?;
 
-                let connected = client.get("connected")
-                    .and_then(|c| c.as_bool())
-                    .unwrap_or(false);
+    clients_output(&clients)
+}
 
-                let status = if connected { "connected" } else { "disconnected" };
-
-                data.push(vec![client_id, status.to_string(), group_id.clone(), stream_id.clone()]);
-            }
-        }
-    }
-
-    print_table(headers, data);
-
-    Ok(())
-}
\ No newline at end of file
+/// Builds the client list shared by `get clients` and `delete client(s)`.
+pub fn clients_output(clients: &[ClientInfo]) -> Result<Output> {
+    let headers = vec!["CLIENT ID", "STATUS", "GROUP ID", "STREAM ID"];
+    let data = clients.iter()
+        .map(|info| {
+            let (group_id, stream_id) = group_and_stream(info);
+            vec![
+                info.client.id.clone(),
+                info.client.status().to_string(),
+                group_id,
+                stream_id,
+            ]
+        })
+        .collect();
+
+    let wide_headers = vec!["CLIENT ID", "STATUS", "NAME", "IP", "MAC", "VERSION", "MUTED", "VOLUME", "LATENCY", "GROUP ID", "STREAM ID"];
+    let wide_data = clients.iter()
+        .map(|info| {
+            let client = &info.client;
+            let (group_id, stream_id) = group_and_stream(info);
+            vec![
+                client.id.clone(),
+                client.status().to_string(),
+                client.config.name.clone(),
+                client.host.ip.clone(),
+                client.host.mac.clone(),
+                client.snapclient.version.clone(),
+                client.config.volume.muted.to_string(),
+                client.config.volume.percent.to_string(),
+                client.config.latency.to_string(),
+                group_id,
+                stream_id,
+            ]
+        })
+        .collect();
+
+    let table = Table::new(headers, data).empty_message(NO_CLIENTS);
+    let wide = Table::new(wide_headers, wide_data).empty_message(NO_CLIENTS);
+    Ok(Output::new(&clients, table)?.with_wide(wide))
+}
+
+fn group_and_stream(info: &ClientInfo) -> (String, String) {
+    info.group.as_ref()
+        .map(|group| (group.id.clone(), group.stream_id.clone()))
+        .unwrap_or_default()
+}
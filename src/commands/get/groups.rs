@@ -1,62 +1,47 @@
-use crate::rpc::client::SnapcastRpcClient;
-use crate::utils::display::print_table;
+use snapctl::operations;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::utils::display::Table;
+use crate::utils::output::Output;
 use anyhow::Result;
 
-pub async fn get_groups(server_url: &str) -> Result<()> {
-    let client = SnapcastRpcClient::new(server_url);
-    let server_info = client.get_status().await?;
+pub async fn get_groups(client: &SnapcastRpcClient) -> Result<Output> {
+    let groups = operations::group::list_groups(client).await?;
 
     let headers = vec!["GROUP ID", "NAME", "STATUS", "STREAM ID", "CLIENTS"];
     let mut data = Vec::new();
 
-    // Get groups array
-    let groups = if let Some(groups) = server_info.get("groups").and_then(|g| g.as_array()) {
-        groups
-    } else {
-        println!("No groups found.");
-        return Ok(());
-    };
-
-    if groups.is_empty() {
-        println!("No groups found.");
-        return Ok(());
-    }
+    let wide_headers = vec!["GROUP ID", "NAME", "STATUS", "STREAM ID", "CLIENTS", "CONNECTED", "CLIENT NAMES"];
+    let mut wide_data = Vec::new();
 
     // Process each group
-    for group in groups {
-        let group_id = group.get("id")
-            .and_then(|id| id.as_str())
-            .unwrap_or("unknown").to_string();
-
-        // Show empty string for undefined names instead of "undefined"
-        let name = group.get("name")
-            .and_then(|n| n.as_str())
-            .unwrap_or("").to_string();
-
-        let muted = group.get("muted")
-            .and_then(|m| m.as_bool())
-            .unwrap_or(false);
-
-        let status = if muted { "muted" } else { "unmuted" };
-
-        let stream_id = group.get("stream_id")
-            .and_then(|id| id.as_str())
-            .unwrap_or("none").to_string();
-
-        let clients = group.get("clients")
-            .and_then(|c| c.as_array())
-            .map(|clients| {
-                clients.iter()
-                    .filter_map(|client| client.get("id").and_then(|id| id.as_str()))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            })
-            .unwrap_or_else(|| "None".to_string());
-
-        data.push(vec![group_id, name, status.to_string(), stream_id, clients]);
+    for group in &groups {
+        let status = if group.muted { "muted" } else { "unmuted" };
+        let stream_id = if group.stream_id.is_empty() { "none" } else { &group.stream_id };
+
+        data.push(vec![
+            group.id.clone(),
+            group.name.clone(),
+            status.to_string(),
+            stream_id.to_string(),
+            group.client_ids().join(", "),
+        ]);
+
+        let connected = group.clients.iter().filter(|c| c.connected).count();
+        let client_names: Vec<&str> = group.clients.iter()
+            .map(|c| if c.config.name.is_empty() { c.host.name.as_str() } else { c.config.name.as_str() })
+            .collect();
+        wide_data.push(vec![
+            group.id.clone(),
+            group.name.clone(),
+            status.to_string(),
+            stream_id.to_string(),
+            group.client_ids().join(", "),
+            format!("{}/{}", connected, group.clients.len()),
+            client_names.join(", "),
+        ]);
     }
 
-    print_table(headers, data);
-
-    Ok(())
-}
\ No newline at end of file
+    let table = Table::new(headers, data).empty_message("No groups found.");
+    let wide = Table::new(wide_headers, wide_data).empty_message("No groups found.");
+    Ok(Output::new(&groups, table)?.with_wide(wide))
+}
@@ -1,6 +1,9 @@
+pub mod artwork;
 pub mod client;
 pub mod clients;
 pub mod group;
 pub mod groups;
+pub mod server;
 pub mod stream;
-pub mod streams;
\ No newline at end of file
+pub mod streams;
+pub mod watch;
\ No newline at end of file
@@ -0,0 +1,28 @@
+use snapctl::operations;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::utils::display::Table;
+use crate::utils::output::Output;
+use anyhow::Result;
+
+pub async fn get_server(client: &SnapcastRpcClient) -> Result<Output> {
+    let summary = operations::server::get_server(client).await?;
+    let host = &summary.host;
+    let snapserver = &summary.snapserver;
+
+    let headers = vec!["HOST", "IP", "OS", "ARCH", "SERVER", "VERSION", "PROTOCOL", "RPC", "GROUPS", "CLIENTS", "STREAMS"];
+    let data = vec![vec![
+        host.name.clone(),
+        host.ip.clone(),
+        host.os.clone(),
+        host.arch.clone(),
+        snapserver.name.clone(),
+        snapserver.version.clone(),
+        snapserver.control_protocol_version.to_string(),
+        summary.rpc_version.to_string(),
+        summary.groups.to_string(),
+        format!("{} connected, {} disconnected", summary.clients_connected, summary.clients_disconnected),
+        format!("{} ({} playing)", summary.streams, summary.streams_playing),
+    ]];
+
+    Output::new(&summary, Table::new(headers, data))
+}
@@ -1,150 +1,134 @@
-use crate::rpc::client::SnapcastRpcClient;
-use crate::utils::display::print_table;
-use anyhow::{Result, Context};
-use serde_json::Value;
-
-pub async fn get_stream(server_url: &str, stream_id: &str) -> Result<()> {
-    let client = SnapcastRpcClient::new(server_url);
-    let server_info = client.get_status().await?;
-
-    // Extract server version
-    let version = server_info["server"]["snapserver"]["version"]
-        .as_str()
-        .unwrap_or("unknown");
-
-    // Find the specified stream
-    let stream = find_stream(&server_info, stream_id)
-        .with_context(|| {
-            let available_streams: Vec<String> = get_available_streams(&server_info);
-            format!("Stream with ID '{}' not found. Available streams: {:?}", stream_id, available_streams)
-        })?;
-
-    // Extract stream information
-    let stream_id_str = stream.get("id")
-        .and_then(|id| id.as_str())
-        .unwrap_or("unknown");
-
-    let status = stream.get("status")
-        .and_then(|s| s.as_str())
-        .unwrap_or("unknown");
-
-    let uri = stream.get("uri")
-        .and_then(|u| u.get("raw"))
-        .and_then(|r| r.as_str())
-        .unwrap_or("unknown");
-
-    // Find groups associated with this stream
-    let groups = find_groups_for_stream(&server_info, stream_id_str);
-
-    let headers = vec!["STREAM ID", "STATUS", "VERSION", "GROUP ID", "CLIENTS", "URI"];
+use snapctl::models::{Group, Stream};
+use snapctl::operations;
+use snapctl::operations::stream::StreamDetails;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::commands::get::artwork::ArtworkFile;
+use crate::utils::display::Table;
+use crate::utils::output::Output;
+use anyhow::Result;
+
+pub async fn get_stream(client: &SnapcastRpcClient, stream_id: &str, artwork: Option<&ArtworkFile>) -> Result<Output> {
+    let details = operations::stream::get_stream(client, stream_id).await?;
+
+    if let Some(artwork) = artwork {
+        artwork.update(&details.stream).await?;
+    }
+
+    stream_output(&details)
+}
+
+/// Builds the stream details shared by `get stream` and `create stream`.
+pub fn stream_output(details: &StreamDetails) -> Result<Output> {
+    let stream = &details.stream;
+    let version = &details.server_version;
+
+    let uri = &stream.uri.raw;
+
+    // Groups associated with this stream
+    let groups = &details.groups;
+
+    let playback = stream.playback_status().unwrap_or_default().to_string();
+    let album = stream.metadata().and_then(|metadata| metadata.album).unwrap_or_default();
+
+    let headers = vec!["STREAM ID", "STATUS", "PLAYBACK", "NOW PLAYING", "ALBUM", "POSITION", "VERSION", "GROUP ID", "CLIENTS", "URI"];
     let mut data = Vec::new();
 
     if groups.is_empty() {
         // If no groups found, print a single line with the stream info
         data.push(vec![
-            stream_id_str.to_string(),
-            status.to_string(),
-            version.to_string(),
+            stream.id.clone(),
+            stream.status.clone(),
+            playback.clone(),
+            now_playing(stream),
+            album.clone(),
+            position(stream),
+            version.clone(),
             "None".to_string(),
             "None".to_string(),
-            uri.to_string(),
+            uri.clone(),
         ]);
     } else {
         // Print first row with all information including version
         let first_group = &groups[0];
-        let first_group_id = first_group.get("id")
-            .and_then(|id| id.as_str())
-            .unwrap_or("unknown").to_string();
-
-        let first_clients = get_client_ids(first_group);
 
         data.push(vec![
-            stream_id_str.to_string(),
-            status.to_string(),
-            version.to_string(),
-            first_group_id,
-            first_clients,
-            uri.to_string(),
+            stream.id.clone(),
+            stream.status.clone(),
+            playback,
+            now_playing(stream),
+            album,
+            position(stream),
+            version.clone(),
+            first_group.id.clone(),
+            get_client_ids(first_group),
+            uri.clone(),
         ]);
 
         // Print subsequent rows with only group ID, clients, and URI
         for group in groups.iter().skip(1) {
-            let group_id = group.get("id")
-                .and_then(|id| id.as_str())
-                .unwrap_or("unknown").to_string();
-
-            let clients = get_client_ids(group);
-
             data.push(vec![
                 "".to_string(),
                 "".to_string(),
                 "".to_string(),
-                group_id,
-                clients,
-                uri.to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                group.id.clone(),
+                get_client_ids(group),
+                uri.clone(),
             ]);
         }
     }
 
-    print_table(headers, data);
-
-    Ok(())
+    Output::new(details, Table::new(headers, data))
 }
 
-/// Helper function to get all available stream IDs for debugging
-fn get_available_streams(server_info: &Value) -> Vec<String> {
-    server_info.get("streams")
-        .and_then(|s| s.as_array())
-        .map(|streams| {
-            streams.iter()
-                .filter_map(|s| s.get("id").and_then(|id| id.as_str()))
-                .map(|id| id.to_string())
-                .collect()
-        })
-        .unwrap_or_default()
+/// Helper function to get client IDs for a group
+fn get_client_ids(group: &Group) -> String {
+    if group.clients.is_empty() {
+        "None".to_string()
+    } else {
+        group.client_ids().join(", ")
+    }
 }
 
-/// Find a stream by ID in the JSON structure
-fn find_stream(server_info: &Value, stream_id: &str) -> Option<Value> {
-    server_info.get("streams")
-        .and_then(|streams| streams.as_array())
-        .and_then(|streams| {
-            streams.iter().find(|stream| {
-                stream.get("id")
-                    .and_then(|id| id.as_str())
-                    .map(|id| id == stream_id)
-                    .unwrap_or(false)
-            }).cloned()
-        })
+/// The current track as `Artist - Title`, or whichever of the two is known.
+pub fn now_playing(stream: &Stream) -> String {
+    let Some(metadata) = stream.metadata() else {
+        return String::new();
+    };
+    let artists = metadata.artist.join(", ");
+    match metadata.title {
+        Some(title) if !artists.is_empty() => format!("{} - {}", artists, title),
+        Some(title) => title,
+        None => artists,
+    }
 }
 
-/// Find groups associated with a stream ID
-fn find_groups_for_stream(server_info: &Value, stream_id: &str) -> Vec<Value> {
-    server_info.get("groups")
-        .and_then(|g| g.as_array())
-        .map(|groups| {
-            groups.iter()
-                .filter(|group| {
-                    group.get("stream_id")
-                        .and_then(|id| id.as_str())
-                        .map(|id| id == stream_id)
-                        .unwrap_or(false)
-                })
-                .cloned()
-                .collect()
-        })
-        .unwrap_or_default()
+/// Playback position such as `1:03 / 5:54 (17%)`, or just `1:03` when the track length is unknown.
+pub fn position(stream: &Stream) -> String {
+    let Some(position) = stream.position() else {
+        return String::new();
+    };
+    match stream.metadata().and_then(|metadata| metadata.duration).filter(|&duration| duration > 0.0) {
+        Some(duration) => format!(
+            "{} / {} ({:.0}%)",
+            format_seconds(position),
+            format_seconds(duration),
+            (position / duration * 100.0).clamp(0.0, 100.0)
+        ),
+        None => format_seconds(position),
+    }
 }
 
-/// Helper function to get client IDs for a group
-fn get_client_ids(group: &Value) -> String {
-    group.get("clients")
-        .and_then(|c| c.as_array())
-        .map(|clients| {
-            clients.iter()
-                .filter_map(|client| client.get("id").and_then(|id| id.as_str()))
-                .collect::<Vec<_>>()
-                .join(", ")
-        })
-        .unwrap_or_else(|| "None".to_string())
-}
\ No newline at end of file
+/// Formats seconds as `m:ss`, or `h:mm:ss` from an hour on.
+fn format_seconds(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
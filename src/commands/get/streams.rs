@@ -1,23 +1,44 @@
-use crate::rpc::client::SnapcastRpcClient;
-use crate::utils::display::print_table;
+use snapctl::models::Server;
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::commands::get::stream::{now_playing, position};
+use crate::utils::display::Table;
+use crate::utils::output::Output;
 use anyhow::Result;
 
-pub async fn get_streams(server_url: &str) -> Result<()> {
-    let client = SnapcastRpcClient::new(server_url);
+pub async fn get_streams(client: &SnapcastRpcClient) -> Result<Output> {
     let server_info = client.get_status().await?;
 
-    let headers = vec!["STREAM ID", "STATUS"];
-    let mut data = Vec::new();
+    streams_output(&server_info)
+}
+
+/// Builds the stream list shared by `get streams` and `delete stream`.
+pub fn streams_output(server_info: &Server) -> Result<Output> {
+    let streams = &server_info.streams;
 
-    if let Some(streams) = server_info["streams"].as_array() {
-        for stream in streams {
-            let id = stream["id"].as_str().unwrap_or("unknown").to_string();
-            let status = stream["status"].as_str().unwrap_or("unknown").to_string();
-            data.push(vec![id, status]);
-        }
-    }
+    let headers = vec!["STREAM ID", "STATUS"];
+    let data = streams.iter()
+        .map(|stream| vec![stream.id.clone(), stream.status.clone()])
+        .collect();
 
-    print_table(headers, data);
+    let wide_headers = vec!["STREAM ID", "STATUS", "PLAYBACK", "NOW PLAYING", "POSITION", "GROUPS", "URI"];
+    let wide_data = streams.iter()
+        .map(|stream| {
+            let groups: Vec<&str> = server_info.groups_for_stream(&stream.id).iter()
+                .map(|group| group.id.as_str())
+                .collect();
+            vec![
+                stream.id.clone(),
+                stream.status.clone(),
+                stream.playback_status().unwrap_or_default().to_string(),
+                now_playing(stream),
+                position(stream),
+                groups.join(", "),
+                stream.uri.raw.clone(),
+            ]
+        })
+        .collect();
 
-    Ok(())
-}
\ No newline at end of file
+    let table = Table::new(headers, data).empty_message("No streams found.");
+    let wide = Table::new(wide_headers, wide_data).empty_message("No streams found.");
+    Ok(Output::new(streams, table)?.with_wide(wide))
+}
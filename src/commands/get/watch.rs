@@ -0,0 +1,111 @@
+use std::future::Future;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use futures::{FutureExt, StreamExt};
+use snapctl::rpc::client::SnapcastRpcClient;
+use snapctl::rpc::events::{EventFilter, EventKind};
+use crate::cli::GetSubcommand;
+use crate::utils::output::{Output, OutputFormat, OutputOptions};
+
+/// Time allowed for a burst of notifications to settle before redrawing.
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// Clears the terminal and moves the cursor to the top left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Notifications that can change the table shown by a get subcommand.
+pub fn relevant_events(subcommand: &GetSubcommand) -> Vec<EventKind> {
+    let mut kinds = vec![EventKind::ServerUpdate, EventKind::ClientConnect, EventKind::ClientDisconnect];
+    match subcommand {
+        GetSubcommand::Server => kinds.push(EventKind::StreamUpdate),
+        GetSubcommand::Streams | GetSubcommand::Stream { .. } => {
+            kinds.extend([EventKind::StreamUpdate, EventKind::StreamProperties, EventKind::GroupStream]);
+        }
+        // Groups show their clients' names, and their full objects every client setting.
+        GetSubcommand::Groups | GetSubcommand::Group { .. } => {
+            kinds.extend([
+                EventKind::GroupMute,
+                EventKind::GroupStream,
+                EventKind::GroupName,
+                EventKind::ClientName,
+                EventKind::ClientVolume,
+                EventKind::ClientLatency,
+                EventKind::StreamProperties,
+            ]);
+        }
+        GetSubcommand::Clients | GetSubcommand::Client { .. } => {
+            kinds.extend([
+                EventKind::ClientVolume,
+                EventKind::ClientLatency,
+                EventKind::ClientName,
+                EventKind::GroupStream,
+                EventKind::StreamProperties,
+            ]);
+        }
+    }
+    kinds
+}
+
+/// Redraws the output built by `render` whenever one of `events` arrives, and every `interval`
+/// when none do. In the table formats, cells that changed since the previous draw are highlighted.
+///
+/// Runs until interrupted or until the table can no longer be built.
+pub async fn watch<F, Fut>(client: &SnapcastRpcClient, events: &[EventKind], interval: Duration, options: &OutputOptions, render: F) -> Result<()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<Output>>,
+{
+    if interval.is_zero() {
+        return Err(anyhow!("The watch interval must be greater than zero"));
+    }
+
+    let terminal = std::io::stdout().is_terminal();
+    let mut notifications = Box::pin(client.events(EventFilter { kinds: events.to_vec(), ids: Vec::new() }));
+    let mut notifications_open = true;
+    let mut ticker = tokio::time::interval(interval);
+    let mut previous: Option<Output> = None;
+
+    loop {
+        let output = render().await?;
+        if previous.as_ref() != Some(&output) {
+            draw(&output, previous.as_ref(), interval, options, terminal)?;
+            previous = Some(output);
+        }
+
+        ticker.reset();
+        tokio::select! {
+            _ = ticker.tick() => {}
+            notification = notifications.next(), if notifications_open => {
+                if notification.is_none() {
+                    // Notifications stopped, keep polling until the server stops answering.
+                    notifications_open = false;
+                    continue;
+                }
+                // A single change often produces several notifications, redraw once for all of them.
+                tokio::time::sleep(SETTLE_DELAY).await;
+                while let Some(Some(_)) = notifications.next().now_or_never() {}
+            }
+        }
+    }
+}
+
+/// Prints the output, replacing the previous draw when stdout is a terminal.
+fn draw(output: &Output, previous: Option<&Output>, interval: Duration, options: &OutputOptions, terminal: bool) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    if terminal {
+        write!(stdout, "{}", CLEAR_SCREEN)?;
+        writeln!(stdout, "Redrawing on changes and every {:?}, press Ctrl+C to stop.\n", interval)?;
+        write!(stdout, "{}", output.render(options, previous)?)?;
+    } else {
+        // Keep piped output free of escape codes, with a blank line between draws.
+        if previous.is_some() && options.format.is_table() {
+            writeln!(stdout)?;
+        } else if previous.is_some() && options.format == OutputFormat::Yaml {
+            writeln!(stdout, "---")?;
+        }
+        write!(stdout, "{}", output.render(options, None)?)?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
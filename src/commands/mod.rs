@@ -1,6 +1,9 @@
+pub mod create;
 pub mod delete;
 pub mod get;
 pub mod set;
+pub mod stream;
 pub mod version;
+pub mod watch;
 
 
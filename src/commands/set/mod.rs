@@ -1,2 +1,3 @@
 pub mod client;
-pub mod group;
\ No newline at end of file
+pub mod group;
+pub mod stream;
\ No newline at end of file
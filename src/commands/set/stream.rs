@@ -0,0 +1,27 @@
+use snapctl::operations;
+use snapctl::operations::stream::{LoopStatus, StreamChanges};
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::commands::get::stream::stream_output;
+use crate::utils::output::Output;
+use anyhow::{anyhow, Result};
+
+pub async fn set_stream(
+    client: &SnapcastRpcClient,
+    stream_id: &str,
+    loop_status: Option<LoopStatus>,
+    shuffle: Option<bool>,
+    volume: Option<i64>,
+    mute: Option<bool>,
+    rate: Option<f64>,
+) -> Result<Output> {
+    let changes = StreamChanges { loop_status, shuffle, volume, mute, rate };
+
+    // If no parameters were set
+    if changes.is_empty() {
+        return Err(anyhow!("No parameters specified to set. Use --loop-status, --shuffle, --volume, --mute, or --rate."));
+    }
+
+    let details = operations::stream::set_stream(client, stream_id, &changes).await?;
+
+    stream_output(&details)
+}
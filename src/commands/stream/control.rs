@@ -0,0 +1,37 @@
+use anyhow::Result;
+use snapctl::operations;
+use snapctl::operations::stream::{PlaybackCommand, StreamTarget};
+use snapctl::rpc::client::SnapcastRpcClient;
+use crate::cli::{StreamControlCommand, StreamTargetArgs};
+use crate::commands::get::stream::stream_output;
+use crate::utils::output::Output;
+
+/// Sends a playback command to a stream and shows the stream.
+pub async fn control_stream(client: &SnapcastRpcClient, command: StreamControlCommand) -> Result<Output> {
+    let (target, command) = match command {
+        StreamControlCommand::Play(target) => (target, PlaybackCommand::Play),
+        StreamControlCommand::Pause(target) => (target, PlaybackCommand::Pause),
+        StreamControlCommand::PlayPause(target) => (target, PlaybackCommand::PlayPause),
+        StreamControlCommand::Stop(target) => (target, PlaybackCommand::Stop),
+        StreamControlCommand::Next(target) => (target, PlaybackCommand::Next),
+        StreamControlCommand::Previous(target) => (target, PlaybackCommand::Previous),
+        StreamControlCommand::Seek { target, offset } => (target, PlaybackCommand::Seek(offset)),
+        StreamControlCommand::SetPosition { target, position } => {
+            (target, PlaybackCommand::SetPosition(position.as_secs_f64()))
+        }
+    };
+
+    let details = operations::stream::control_stream(client, &stream_target(target), command).await?;
+
+    stream_output(&details)
+}
+
+/// Converts the target options, of which clap guarantees exactly one is set.
+pub fn stream_target(target: StreamTargetArgs) -> StreamTarget {
+    match (target.stream_id, target.group, target.client) {
+        (Some(stream_id), _, _) => StreamTarget::Stream(stream_id),
+        (_, Some(group), _) => StreamTarget::Group(group),
+        (_, _, Some(client_id)) => StreamTarget::Client(client_id),
+        (None, None, None) => unreachable!("clap requires a stream, --group or --client"),
+    }
+}
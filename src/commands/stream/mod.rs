@@ -0,0 +1 @@
+pub mod control;
\ No newline at end of file
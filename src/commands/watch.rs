@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use snapctl::models::{Client, Server};
+use snapctl::rpc::client::SnapcastRpcClient;
+use snapctl::rpc::events::{Event, EventKind};
+use snapctl::rpc::mirror::ServerMirror;
+use snapctl::rpc::transport::ConnectionOptions;
+use crate::cli::{WatchArgs, WatchFormat};
+
+/// Shortest wait between reconnection attempts, even with `--retry-backoff 0`.
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Prints server notifications until interrupted.
+///
+/// After a dropped connection, reconnects with exponential backoff and prints the
+/// freshly loaded state as a `Server.OnUpdate` event, so nothing missed goes unnoticed.
+/// Connection status messages go to stderr.
+pub async fn watch(server_url: &str, options: &ConnectionOptions, args: WatchArgs) -> Result<()> {
+    if server_url.starts_with("http") {
+        return Err(anyhow!("The http transport does not receive notifications, use ws or tcp to watch the server"));
+    }
+
+    let args = Arc::new(args);
+    let mut connected_before = false;
+    let mut attempt = 0;
+
+    loop {
+        let printer_args = args.clone();
+        let printer = move |event: &Event, server: &Server| print_event(&printer_args, event, server);
+        let mirror = match connect(server_url, options, printer).await {
+            Ok(mirror) => mirror,
+            // Give up right away if the server was never reachable
+            Err(e) if !connected_before => return Err(e),
+            Err(e) => {
+                attempt += 1;
+                let delay = options.retry.delay(attempt).max(MIN_RECONNECT_DELAY);
+                eprintln!("Reconnecting in {:?}: {:#}", delay, e);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+
+        if connected_before {
+            eprintln!("Reconnected to {}, state resynced", server_url);
+            let server = mirror.snapshot();
+            print_event(&args, &Event::ServerUpdated { server: server.clone() }, &server);
+        } else {
+            eprintln!("Watching {}", server_url);
+        }
+        connected_before = true;
+        attempt = 0;
+
+        mirror.closed().await;
+
+        eprintln!("Connection to {} lost", server_url);
+    }
+}
+
+/// Connects and starts mirroring the server, with `printer` seeing every change from the start.
+async fn connect(
+    server_url: &str,
+    options: &ConnectionOptions,
+    printer: impl Fn(&Event, &Server) + Send + Sync + 'static,
+) -> Result<ServerMirror> {
+    let client = SnapcastRpcClient::connect(server_url, options).await?;
+    ServerMirror::start_with(Arc::new(client), printer).await
+}
+
+fn print_event(args: &WatchArgs, event: &Event, server: &Server) {
+    if !matches_filters(args, event, server) {
+        return;
+    }
+
+    match args.format {
+        WatchFormat::Text => println!("{}", describe(event, server)),
+        WatchFormat::Json => match serde_json::to_string(event) {
+            Ok(line) => println!("{}", line),
+            Err(e) => eprintln!("Failed to serialize event: {}", e),
+        },
+    }
+}
+
+/// Whether an event passes the `--event`, `--client`, `--group` and `--stream` filters.
+fn matches_filters(args: &WatchArgs, event: &Event, server: &Server) -> bool {
+    if !args.events.is_empty() && !args.events.iter().any(|&kind| EventKind::from(kind) == event.kind()) {
+        return false;
+    }
+    if args.clients.is_empty() && args.groups.is_empty() && args.streams.is_empty() {
+        return true;
+    }
+
+    let Some(id) = event.object_id() else {
+        return false;
+    };
+    match event.kind() {
+        EventKind::ClientConnect | EventKind::ClientDisconnect | EventKind::ClientVolume
+        | EventKind::ClientLatency | EventKind::ClientName => args.clients.iter().any(|wanted| {
+            wanted == id || server.find_client(id).is_some_and(|client| display_name(client) == wanted)
+        }),
+        EventKind::GroupMute | EventKind::GroupStream | EventKind::GroupName => args.groups.iter().any(|wanted| {
+            wanted == id || server.find_group(wanted).is_some_and(|group| group.id == id)
+        }),
+        EventKind::StreamUpdate | EventKind::StreamProperties => args.streams.iter().any(|wanted| wanted == id),
+        EventKind::ServerUpdate | EventKind::Other => false,
+    }
+}
+
+/// Formats an event as a human-readable line, naming objects from the current state.
+fn describe(event: &Event, server: &Server) -> String {
+    let client_label = |id: &str| match server.find_client(id).map(display_name) {
+        Some(name) if !name.is_empty() => format!("client {} ({})", id, name),
+        _ => format!("client {}", id),
+    };
+    let group_label = |id: &str| match server.find_group(id) {
+        Some(group) if !group.name.is_empty() => format!("group {} ({})", id, group.name),
+        _ => format!("group {}", id),
+    };
+
+    match event {
+        Event::ClientConnected { id, .. } => format!("{} connected", client_label(id)),
+        Event::ClientDisconnected { id, .. } => format!("{} disconnected", client_label(id)),
+        Event::ClientVolumeChanged { id, volume } => format!(
+            "{} volume {}%{}",
+            client_label(id),
+            volume.percent,
+            if volume.muted { ", muted" } else { "" }
+        ),
+        Event::ClientLatencyChanged { id, latency } => format!("{} latency {}ms", client_label(id), latency),
+        Event::ClientNameChanged { id, name } => format!("client {} renamed to '{}'", id, name),
+        Event::GroupMuteChanged { id, mute } => format!("{} {}", group_label(id), if *mute { "muted" } else { "unmuted" }),
+        Event::GroupStreamChanged { id, stream_id } => format!("{} switched to stream {}", group_label(id), stream_id),
+        Event::GroupNameChanged { id, name } => format!("group {} renamed to '{}'", id, name),
+        Event::StreamUpdated { id, stream } => format!("stream {} {}", id, stream.status),
+        Event::StreamPropertiesChanged { id, .. } => format!("stream {} properties changed", id),
+        Event::ServerUpdated { server } => format!(
+            "server state updated: {} groups, {} clients, {} streams",
+            server.groups.len(),
+            server.clients().count(),
+            server.streams.len()
+        ),
+        Event::Other { method, params } => format!("{} {}", method, params),
+    }
+}
+
+/// Configured name of a client, falling back to its host name.
+fn display_name(client: &Client) -> &str {
+    if client.config.name.is_empty() { &client.host.name } else { &client.config.name }
+}
@@ -0,0 +1,9 @@
+//! Control library for Snapcast servers.
+//!
+//! [`rpc::client::SnapcastRpcClient`] speaks JSON-RPC to a snapserver over WebSocket,
+//! raw TCP or HTTP. [`models`] holds the typed server state, and [`operations`] builds
+//! common tasks on top of the client, returning data instead of printing it.
+
+pub mod models;
+pub mod operations;
+pub mod rpc;
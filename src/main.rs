@@ -1,25 +1,68 @@
 mod cli;
 mod commands;
-mod rpc;
 mod utils;
 
 use clap::Parser;
-use anyhow::Result;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use anyhow::{anyhow, Context, Result};
+use snapctl::rpc::auth::Credentials;
+use snapctl::rpc::client::SnapcastRpcClient;
+use snapctl::rpc::transport::{ConnectionOptions, RetryPolicy};
+use snapctl::rpc::transport::endpoint::Endpoint;
+use snapctl::rpc::transport::tls::TlsOptions;
+use commands::get::artwork::ArtworkFile;
+use utils::output::{Output, OutputOptions};
 
 /// Main entry point for the application.
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli_args = cli::Cli::parse();
-    let server_url = get_server_url(&cli_args.host, cli_args.port);
+    let endpoint = get_endpoint(&cli_args)?;
+    let server_url = endpoint.url.to_string();
+    let mut options = get_connection_options(&cli_args)?;
+    // Credentials given as options take precedence over those in the URL.
+    if options.auth.is_none() {
+        options.auth = endpoint.credentials;
+    }
+    let terminal = std::io::stdout().is_terminal();
+    let output = OutputOptions {
+        color: match cli_args.color {
+            cli::ColorMode::Always => true,
+            cli::ColorMode::Never => false,
+            cli::ColorMode::Auto => terminal && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        },
+        // Only tables shown in a terminal are fitted to it, piped output keeps every character.
+        max_width: terminal.then(terminal_width).flatten(),
+        format: cli_args.output,
+        sort_by: cli_args.sort_by,
+        no_headers: cli_args.no_headers,
+    };
     match cli_args.command {
         cli::Command::Get(get_args) => {
-            handle_get_command(&server_url, get_args).await?;
+            let client = SnapcastRpcClient::connect(&server_url, &options).await?;
+            handle_get_command(&client, get_args, &output, &server_url, &options).await?;
         }
         cli::Command::Set(set_args) => {
-            handle_set_command(&server_url, set_args).await?;
+            let client = SnapcastRpcClient::connect(&server_url, &options).await?;
+            handle_set_command(&client, set_args).await?.print(&output)?;
+        }
+        cli::Command::Create(create_args) => {
+            let client = SnapcastRpcClient::connect(&server_url, &options).await?;
+            handle_create_command(&client, create_args).await?.print(&output)?;
         }
         cli::Command::Delete(delete_args) => {
-            handle_delete_command(&server_url, delete_args).await?;
+            let client = SnapcastRpcClient::connect(&server_url, &options).await?;
+            handle_delete_command(&client, delete_args).await?.print(&output)?;
+        }
+        cli::Command::Stream(stream_args) => {
+            let client = SnapcastRpcClient::connect(&server_url, &options).await?;
+            commands::stream::control::control_stream(&client, stream_args.command).await?.print(&output)?;
+        }
+        cli::Command::Watch(watch_args) => {
+            commands::watch::watch(&server_url, &options, watch_args).await?;
         }
         cli::Command::Version => {
             commands::version::get_version()?;
@@ -28,59 +71,172 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Constructs the server URL from the host and port.
-fn get_server_url(host: &str, port: u16) -> String {
-    format!("ws://{}:{}/jsonrpc", host, port)
+/// Resolves the server endpoint from `--url`, or from the `--host`/`--port` shorthand.
+fn get_endpoint(cli_args: &cli::Cli) -> Result<Endpoint> {
+    match &cli_args.url {
+        Some(url) => Endpoint::parse(url),
+        None => Endpoint::from_host(&cli_args.host, cli_args.port, cli_args.transport.into(), cli_args.tls),
+    }
+}
+
+/// Collects the connection settings given on the command line.
+fn get_connection_options(cli_args: &cli::Cli) -> Result<ConnectionOptions> {
+    Ok(ConnectionOptions {
+        tls: TlsOptions {
+            ca_cert: cli_args.ca_cert.clone(),
+            client_cert: cli_args.client_cert.clone(),
+            client_key: cli_args.client_key.clone(),
+            insecure: cli_args.insecure,
+        },
+        auth: get_credentials(cli_args)?,
+        connect_timeout: non_zero(cli_args.connect_timeout),
+        request_timeout: non_zero(cli_args.request_timeout),
+        retry: RetryPolicy {
+            retries: cli_args.retries,
+            backoff: cli_args.retry_backoff,
+        },
+    })
+}
+
+/// Width of the terminal stdout is connected to.
+fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size().map(|(width, _)| width.0 as usize)
+}
+
+/// Treats a zero timeout as no timeout at all.
+fn non_zero(timeout: Duration) -> Option<Duration> {
+    (!timeout.is_zero()).then_some(timeout)
+}
+
+/// Resolves the credentials given on the command line, reading secrets from files or commands.
+fn get_credentials(cli_args: &cli::Cli) -> Result<Option<Credentials>> {
+    if let Some(username) = &cli_args.username {
+        let password = if let Some(password) = &cli_args.password {
+            password.clone()
+        } else if let Some(path) = &cli_args.password_file {
+            read_secret_file(path)?
+        } else if let Some(command) = &cli_args.password_command {
+            run_secret_command(command)?
+        } else {
+            return Err(anyhow!("A password is required for user '{}', use --password, --password-file or --password-command", username));
+        };
+        return Ok(Some(Credentials::Basic { username: username.clone(), password }));
+    }
+
+    let token = if let Some(token) = &cli_args.token {
+        token.clone()
+    } else if let Some(path) = &cli_args.token_file {
+        read_secret_file(path)?
+    } else if let Some(command) = &cli_args.token_command {
+        run_secret_command(command)?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(Credentials::Bearer { token }))
+}
+
+/// Reads a secret from the first line of a file.
+fn read_secret_file(path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read secret from {}", path.display()))?;
+    Ok(contents.lines().next().unwrap_or_default().to_string())
+}
+
+/// Reads a secret from the first line printed by a shell command.
+fn run_secret_command(command: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .with_context(|| format!("Failed to run secret command '{}'", command))?;
+    if !output.status.success() {
+        return Err(anyhow!("Secret command '{}' failed with {}", command, output.status));
+    }
+    let stdout = String::from_utf8(output.stdout).context("Secret command printed invalid UTF-8")?;
+    Ok(stdout.lines().next().unwrap_or_default().to_string())
 }
 
 /// Handles the get command and its subcommands.
-async fn handle_get_command(server_url: &str, args: cli::GetArgs) -> Result<()> {
-    match args.subcommand {
+async fn handle_get_command(client: &SnapcastRpcClient, args: cli::GetArgs, output: &OutputOptions, server_url: &str, options: &ConnectionOptions) -> Result<()> {
+    let artwork = match &args.subcommand {
+        cli::GetSubcommand::Stream { artwork: Some(path), .. } => {
+            Some(ArtworkFile::new(path.clone(), server_url, options, args.watch))
+        }
+        _ => None,
+    };
+    let render = || get_output(client, &args.subcommand, artwork.as_ref());
+    if args.watch {
+        let events = commands::get::watch::relevant_events(&args.subcommand);
+        commands::get::watch::watch(client, &events, args.interval, output, render).await?;
+    } else {
+        render().await?.print(output)?;
+    }
+    Ok(())
+}
+
+/// Runs a get subcommand and returns what it shows.
+async fn get_output(client: &SnapcastRpcClient, subcommand: &cli::GetSubcommand, artwork: Option<&ArtworkFile>) -> Result<Output> {
+    match subcommand {
+        cli::GetSubcommand::Server => {
+            commands::get::server::get_server(client).await
+        }
         cli::GetSubcommand::Client { client_id } => {
-            commands::get::client::get_client(server_url, &client_id).await?;
+            commands::get::client::get_client(client, client_id).await
         }
         
         cli::GetSubcommand::Streams => {
-            commands::get::streams::get_streams(server_url).await?;
+            commands::get::streams::get_streams(client).await
         }
-        cli::GetSubcommand::Stream { stream_id } => {
-            commands::get::stream::get_stream(server_url, &stream_id).await?;
+        cli::GetSubcommand::Stream { stream_id, .. } => {
+            commands::get::stream::get_stream(client, stream_id, artwork).await
         }
         cli::GetSubcommand::Groups => {
-            commands::get::groups::get_groups(server_url).await?;
+            commands::get::groups::get_groups(client).await
         }
         cli::GetSubcommand::Group { identifier } => {
-            commands::get::group::get_group(server_url, &identifier).await?;
+            commands::get::group::get_group(client, identifier).await
         }
         cli::GetSubcommand::Clients => {
-            commands::get::clients::get_clients(server_url).await?;
+            commands::get::clients::get_clients(client).await
         }
     }
-    Ok(())
 }
 
 /// Handles the set command and its subcommands.
-async fn handle_set_command(server_url: &str, args: cli::SetArgs) -> Result<()> {
+async fn handle_set_command(client: &SnapcastRpcClient, args: cli::SetArgs) -> Result<Output> {
     match args.subcommand {
         cli::SetSubcommand::Client { client_id, mute, volume, latency, name, group } => {
-            commands::set::client::set_client(server_url, &client_id, mute, volume, latency, name, group).await?;
+            commands::set::client::set_client(client, &client_id, mute, volume, latency, name, group).await
         }
         cli::SetSubcommand::Group { group_id, name, mute, stream_id, clients } => {
-            commands::set::group::set_group(server_url, &group_id, name, mute, stream_id, clients).await?;
+            commands::set::group::set_group(client, &group_id, name, mute, stream_id, clients).await
+        }
+        cli::SetSubcommand::Stream { stream_id, loop_status, shuffle, volume, mute, rate } => {
+            commands::set::stream::set_stream(client, &stream_id, loop_status.map(Into::into), shuffle, volume, mute, rate).await
+        }
+    }
+}
+
+/// Handles the create command and its subcommands.
+async fn handle_create_command(client: &SnapcastRpcClient, args: cli::CreateArgs) -> Result<Output> {
+    match args.subcommand {
+        cli::CreateSubcommand::Stream { source } => {
+            commands::create::stream::create_stream(client, source).await
         }
     }
-    Ok(())
 }
 
 /// Handles the delete command and its subcommands.
-async fn handle_delete_command(server_url: &str, args: cli::DeleteArgs) -> Result<()> {
+async fn handle_delete_command(client: &SnapcastRpcClient, args: cli::DeleteArgs) -> Result<Output> {
     match args.subcommand {
         cli::DeleteSubcommand::Client { client_id } => {
-            commands::delete::client::delete_client(server_url, &client_id).await?;
+            commands::delete::client::delete_client(client, &client_id).await
         }
         cli::DeleteSubcommand::Clients { client_ids } => {
-            commands::delete::clients::delete_clients(server_url, &client_ids).await?;
+            commands::delete::clients::delete_clients(client, &client_ids).await
+        }
+        cli::DeleteSubcommand::Stream { stream_id } => {
+            commands::delete::stream::delete_stream(client, &stream_id).await
         }
     }
-    Ok(())
 }
\ No newline at end of file
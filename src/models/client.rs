@@ -0,0 +1,78 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use super::Host;
+
+/// A snapclient instance known to the server.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Client {
+    pub id: String,
+    #[serde(default)]
+    pub connected: bool,
+    pub config: ClientConfig,
+    #[serde(default)]
+    pub host: Host,
+    #[serde(default)]
+    pub last_seen: LastSeen,
+    #[serde(default)]
+    pub snapclient: Snapclient,
+}
+
+/// Client settings persisted by the server.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientConfig {
+    #[serde(default, deserialize_with = "deserialize_instance")]
+    pub instance: i64,
+    #[serde(default)]
+    pub latency: i64,
+    #[serde(default)]
+    pub name: String,
+    pub volume: Volume,
+}
+
+/// Volume level and mute state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Volume {
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub percent: i64,
+}
+
+/// Time the client was last seen by the server.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LastSeen {
+    pub sec: i64,
+    pub usec: i64,
+}
+
+/// Snapclient software details.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Snapclient {
+    pub name: String,
+    pub protocol_version: i64,
+    pub version: String,
+}
+
+impl Client {
+    /// Returns "connected" or "disconnected".
+    pub fn status(&self) -> &'static str {
+        if self.connected { "connected" } else { "disconnected" }
+    }
+}
+
+/// Some servers report the instance as a string, so accept both forms.
+fn deserialize_instance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Instance {
+        Number(i64),
+        Text(String),
+    }
+
+    match Instance::deserialize(deserializer)? {
+        Instance::Number(n) => Ok(n),
+        Instance::Text(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
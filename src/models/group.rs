@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use super::Client;
+
+/// A group of clients playing the same stream.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Group {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub stream_id: String,
+    #[serde(default)]
+    pub clients: Vec<Client>,
+}
+
+impl Group {
+    /// Returns the IDs of the clients in this group.
+    pub fn client_ids(&self) -> Vec<&str> {
+        self.clients.iter().map(|client| client.id.as_str()).collect()
+    }
+}
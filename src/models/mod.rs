@@ -0,0 +1,9 @@
+pub mod client;
+pub mod group;
+pub mod server;
+pub mod stream;
+
+pub use client::{Client, ClientConfig, LastSeen, Snapclient, Volume};
+pub use group::Group;
+pub use server::{Host, Server, ServerInfo, Snapserver};
+pub use stream::{ArtData, Metadata, Stream, StreamUri};
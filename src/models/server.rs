@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use super::{Client, Group, Stream};
+
+/// Full server state as returned by `Server.GetStatus`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Server {
+    #[serde(default)]
+    pub groups: Vec<Group>,
+    pub server: ServerInfo,
+    #[serde(default)]
+    pub streams: Vec<Stream>,
+}
+
+/// Information about the machine and the snapserver process.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub host: Host,
+    pub snapserver: Snapserver,
+}
+
+/// Host description shared by the server and its clients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Host {
+    pub arch: String,
+    pub ip: String,
+    pub mac: String,
+    pub name: String,
+    pub os: String,
+}
+
+/// Snapserver software details.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Snapserver {
+    pub control_protocol_version: i64,
+    pub name: String,
+    pub protocol_version: i64,
+    pub version: String,
+}
+
+impl Server {
+    /// Iterates over every client of every group.
+    pub fn clients(&self) -> impl Iterator<Item = &Client> {
+        self.groups.iter().flat_map(|group| group.clients.iter())
+    }
+
+    /// Finds a client by ID.
+    pub fn find_client(&self, client_id: &str) -> Option<&Client> {
+        self.clients().find(|client| client.id == client_id)
+    }
+
+    /// Finds a group by ID or name.
+    pub fn find_group(&self, identifier: &str) -> Option<&Group> {
+        self.groups.iter().find(|group| group.id == identifier || group.name == identifier)
+    }
+
+    /// Finds a stream by ID.
+    pub fn find_stream(&self, stream_id: &str) -> Option<&Stream> {
+        self.streams.iter().find(|stream| stream.id == stream_id)
+    }
+
+    /// Finds the group a client belongs to.
+    pub fn group_for_client(&self, client_id: &str) -> Option<&Group> {
+        self.groups.iter().find(|group| group.clients.iter().any(|client| client.id == client_id))
+    }
+
+    /// Returns the groups currently playing a stream.
+    pub fn groups_for_stream(&self, stream_id: &str) -> Vec<&Group> {
+        self.groups.iter().filter(|group| group.stream_id == stream_id).collect()
+    }
+
+    /// Finds a client by ID for modification.
+    pub fn find_client_mut(&mut self, client_id: &str) -> Option<&mut Client> {
+        self.groups.iter_mut()
+            .flat_map(|group| group.clients.iter_mut())
+            .find(|client| client.id == client_id)
+    }
+
+    /// Finds a group by ID for modification.
+    pub fn find_group_mut(&mut self, group_id: &str) -> Option<&mut Group> {
+        self.groups.iter_mut().find(|group| group.id == group_id)
+    }
+
+    /// Finds a stream by ID for modification.
+    pub fn find_stream_mut(&mut self, stream_id: &str) -> Option<&mut Stream> {
+        self.streams.iter_mut().find(|stream| stream.id == stream_id)
+    }
+}
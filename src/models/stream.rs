@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// An audio source configured on the server.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stream {
+    pub id: String,
+    #[serde(default)]
+    pub status: String,
+    pub uri: StreamUri,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<Value>,
+}
+
+/// Now-playing information from `properties.metadata`, as published by the stream's control script.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Metadata {
+    pub title: Option<String>,
+    #[serde(deserialize_with = "deserialize_artists")]
+    pub artist: Vec<String>,
+    pub album: Option<String>,
+    /// Track length in seconds.
+    pub duration: Option<f64>,
+    pub art_url: Option<String>,
+    pub art_data: Option<ArtData>,
+}
+
+/// Cover art embedded in the metadata.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArtData {
+    /// Base64-encoded image.
+    pub data: String,
+    /// Image format, such as `png` or `jpg`.
+    pub extension: String,
+}
+
+/// Parsed form of the URI a stream was configured with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamUri {
+    pub fragment: String,
+    pub host: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    pub raw: String,
+    pub scheme: String,
+}
+
+impl Stream {
+    /// Reads a boolean property such as `canControl` or `canSeek`, if the server reported it.
+    pub fn property_flag(&self, name: &str) -> Option<bool> {
+        self.properties.as_ref()?.get(name)?.as_bool()
+    }
+
+    /// Track information, if the stream publishes any.
+    pub fn metadata(&self) -> Option<Metadata> {
+        let metadata = self.properties.as_ref()?.get("metadata")?;
+        Metadata::deserialize(metadata).ok()
+    }
+
+    /// Playback position within the current track, in seconds.
+    pub fn position(&self) -> Option<f64> {
+        self.properties.as_ref()?.get("position")?.as_f64()
+    }
+
+    /// Player state reported by the control script: `playing`, `paused` or `stopped`.
+    pub fn playback_status(&self) -> Option<&str> {
+        self.properties.as_ref()?.get("playbackStatus")?.as_str()
+    }
+}
+
+/// Accepts a single artist as well as the list the specification asks for.
+fn deserialize_artists<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Artists {
+        List(Vec<String>),
+        Single(String),
+    }
+
+    match Option::<Artists>::deserialize(deserializer)? {
+        Some(Artists::List(artists)) => Ok(artists),
+        Some(Artists::Single(artist)) => Ok(vec![artist]),
+        None => Ok(Vec::new()),
+    }
+}
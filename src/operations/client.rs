@@ -0,0 +1,213 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use serde_json::json;
+use crate::models::{Client, Server, Stream};
+use crate::operations::group::GroupSummary;
+use crate::rpc::client::{check_batch_results, SnapcastRpcClient};
+
+/// A client together with the group it belongs to and the stream that group plays.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientInfo {
+    /// Serialized inline, so the client fields sit next to `group`.
+    #[serde(flatten)]
+    pub client: Client,
+    pub group: Option<GroupSummary>,
+    /// The stream the client's group plays.
+    pub stream: Option<Stream>,
+}
+
+impl ClientInfo {
+    /// Looks up a client and its group in the server state.
+    pub fn find(server_info: &Server, client_id: &str) -> Option<Self> {
+        let client = server_info.find_client(client_id)?;
+        let group = server_info.group_for_client(client_id);
+        Some(ClientInfo {
+            client: client.clone(),
+            group: group.map(GroupSummary::from),
+            stream: group.and_then(|group| server_info.find_stream(&group.stream_id)).cloned(),
+        })
+    }
+
+    /// Every client of the server, in group order.
+    pub fn all(server_info: &Server) -> Vec<Self> {
+        server_info.groups.iter()
+            .flat_map(|group| group.clients.iter().map(move |client| ClientInfo {
+                client: client.clone(),
+                group: Some(GroupSummary::from(group)),
+                stream: server_info.find_stream(&group.stream_id).cloned(),
+            }))
+            .collect()
+    }
+}
+
+/// Group membership change for a client.
+#[derive(Debug, Clone)]
+pub enum GroupChange {
+    /// Move the client into the group with this ID or name.
+    Join(String),
+    /// Take the client out of its group, which gives it a group of its own.
+    Leave,
+}
+
+/// Settings to change on a client. `None` leaves a setting untouched.
+#[derive(Debug, Clone, Default)]
+pub struct ClientChanges {
+    pub mute: Option<bool>,
+    pub volume: Option<i64>,
+    pub latency: Option<i64>,
+    pub name: Option<String>,
+    pub group: Option<GroupChange>,
+}
+
+/// Lists every client with its group.
+pub async fn list_clients(client: &SnapcastRpcClient) -> Result<Vec<ClientInfo>> {
+    Ok(ClientInfo::all(&client.get_status().await?))
+}
+
+/// Finds a client by ID.
+pub async fn get_client(client: &SnapcastRpcClient, client_id: &str) -> Result<ClientInfo> {
+    let server_info = client.get_status().await?;
+
+    ClientInfo::find(&server_info, client_id)
+        .with_context(|| {
+            let available_clients: Vec<String> = get_available_clients(&server_info);
+            format!("Client with ID '{}' not found. Available clients: {:?}", client_id, available_clients)
+        })
+}
+
+/// Applies `changes` to a client in a single batch and returns its updated state.
+pub async fn set_client(client: &SnapcastRpcClient, client_id: &str, changes: &ClientChanges) -> Result<ClientInfo> {
+    // Check if client exists before making any changes
+    let server_info = client.get_status().await?;
+    let current_group = server_info.group_for_client(client_id)
+        .with_context(|| format!("Client with ID '{}' not found", client_id))?;
+
+    // Every change is sent in a single batch
+    let mut calls = Vec::new();
+    let mut labels = Vec::new();
+
+    if let Some(name) = &changes.name {
+        calls.push(("Client.SetName", Some(json!({
+            "id": client_id,
+            "name": name
+        }))));
+        labels.push("Failed to set client name".to_string());
+    }
+
+    if changes.mute.is_some() || changes.volume.is_some() {
+        let mut volume_params = json!({});
+
+        if let Some(mute) = changes.mute {
+            volume_params["muted"] = json!(mute);
+        }
+
+        if let Some(volume) = changes.volume {
+            volume_params["percent"] = json!(volume);
+        }
+
+        calls.push(("Client.SetVolume", Some(json!({
+            "id": client_id,
+            "volume": volume_params
+        }))));
+        labels.push("Failed to set client volume".to_string());
+    }
+
+    if let Some(latency) = changes.latency {
+        calls.push(("Client.SetLatency", Some(json!({
+            "id": client_id,
+            "latency": latency
+        }))));
+        labels.push("Failed to set client latency".to_string());
+    }
+
+    if let Some(group_change) = &changes.group {
+        // Remove the client from its current group, or add it to the specified one
+        let target_group = match group_change {
+            GroupChange::Leave => current_group,
+            GroupChange::Join(identifier) => server_info.find_group(identifier)
+                .with_context(|| format!("Group with identifier '{}' not found", identifier))?,
+        };
+
+        let mut updated_clients: Vec<&str> = target_group.client_ids().into_iter()
+            .filter(|&id| id != client_id)
+            .collect();
+        if let GroupChange::Join(_) = group_change {
+            updated_clients.push(client_id);
+        }
+
+        calls.push(("Group.SetClients", Some(json!({
+            "id": target_group.id,
+            "clients": updated_clients
+        }))));
+        labels.push("Failed to update group clients".to_string());
+    }
+
+    let results = client.batch(calls).await?;
+    check_batch_results(results, &labels)?;
+
+    // Get client, group and stream information after making changes
+    let server_info = client.get_status().await?;
+    ClientInfo::find(&server_info, client_id)
+        .context("Failed to get final client status: Client not found")
+}
+
+/// Sets the volume and/or mute state of a client.
+pub async fn set_volume(client: &SnapcastRpcClient, client_id: &str, percent: Option<i64>, mute: Option<bool>) -> Result<ClientInfo> {
+    let changes = ClientChanges { volume: percent, mute, ..Default::default() };
+    set_client(client, client_id, &changes).await
+}
+
+/// Moves a client into the group with the given ID or name.
+pub async fn move_client(client: &SnapcastRpcClient, client_id: &str, group: &str) -> Result<ClientInfo> {
+    let changes = ClientChanges { group: Some(GroupChange::Join(group.to_string())), ..Default::default() };
+    set_client(client, client_id, &changes).await
+}
+
+/// Deletes a client from the server and returns the remaining clients.
+pub async fn delete_client(client: &SnapcastRpcClient, client_id: &str) -> Result<Vec<ClientInfo>> {
+    client.get_client_status(client_id).await
+        .with_context(|| format!("Failed to get status of client '{}'", client_id))?;
+
+    let params = json!({
+        "id": client_id
+    });
+    client.request("Server.DeleteClient", Some(params)).await
+        .with_context(|| format!("Failed to delete client '{}'", client_id))?;
+
+    list_clients(client).await
+}
+
+/// Deletes several clients in a single batch and returns the remaining clients.
+///
+/// Nothing is deleted unless every client exists.
+pub async fn delete_clients(client: &SnapcastRpcClient, client_ids: &[&str]) -> Result<Vec<ClientInfo>> {
+    if client_ids.is_empty() {
+        return Err(anyhow!("No valid client IDs provided"));
+    }
+
+    // First, check if all clients exist
+    let server_info = client.get_status().await?;
+    for client_id in client_ids {
+        if server_info.find_client(client_id).is_none() {
+            return Err(anyhow!("Client with ID '{}' not found", client_id));
+        }
+    }
+
+    // Delete every client in a single batch
+    let calls = client_ids.iter()
+        .map(|client_id| ("Server.DeleteClient", Some(json!({ "id": client_id }))))
+        .collect();
+    let labels: Vec<String> = client_ids.iter()
+        .map(|client_id| format!("Failed to delete client '{}'", client_id))
+        .collect();
+
+    let results = client.batch(calls).await?;
+    check_batch_results(results, &labels)?;
+
+    list_clients(client).await
+}
+
+/// Helper function to get all available client IDs for debugging
+fn get_available_clients(server_info: &Server) -> Vec<String> {
+    server_info.clients().map(|client| client.id.clone()).collect()
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::models::{Group, Server, Stream};
+use crate::rpc::client::SnapcastRpcClient;
+
+/// A group with the stream it plays and the version of the server it lives on.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupDetails {
+    pub group: Group,
+    pub stream: Option<Stream>,
+    pub server_version: String,
+}
+
+/// The group a client belongs to, without its member list.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupSummary {
+    pub id: String,
+    pub name: String,
+    pub muted: bool,
+    pub stream_id: String,
+}
+
+impl From<&Group> for GroupSummary {
+    fn from(group: &Group) -> Self {
+        GroupSummary {
+            id: group.id.clone(),
+            name: group.name.clone(),
+            muted: group.muted,
+            stream_id: group.stream_id.clone(),
+        }
+    }
+}
+
+/// Settings to change on a group. `None` leaves a setting untouched.
+#[derive(Debug, Clone, Default)]
+pub struct GroupChanges {
+    pub name: Option<String>,
+    pub mute: Option<bool>,
+    /// An empty id detaches the group from any stream.
+    pub stream_id: Option<String>,
+    /// Complete list of member client ids.
+    pub clients: Option<Vec<String>>,
+}
+
+impl GroupChanges {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.mute.is_none() && self.stream_id.is_none() && self.clients.is_none()
+    }
+}
+
+/// Lists every group.
+pub async fn list_groups(client: &SnapcastRpcClient) -> Result<Vec<Group>> {
+    Ok(client.get_status().await?.groups)
+}
+
+/// Finds a group by ID or name.
+pub async fn get_group(client: &SnapcastRpcClient, identifier: &str) -> Result<GroupDetails> {
+    let server_info = client.get_status().await?;
+
+    let group = server_info.find_group(identifier)
+        .with_context(|| {
+            let available_groups: Vec<String> = get_available_groups(&server_info);
+            format!("Group with identifier '{}' not found. Available groups: {:?}", identifier, available_groups)
+        })?;
+
+    Ok(GroupDetails {
+        group: group.clone(),
+        stream: server_info.find_stream(&group.stream_id).cloned(),
+        server_version: server_info.server.snapserver.version.clone(),
+    })
+}
+
+/// Applies `changes` to a group and returns its updated state.
+pub async fn set_group(client: &SnapcastRpcClient, group_id: &str, changes: &GroupChanges) -> Result<Group> {
+    let mut group = client.get_group_status(group_id).await
+        .with_context(|| format!("Failed to get status of group '{}'", group_id))?;
+
+    if let Some(name) = &changes.name {
+        let params = json!({
+            "id": group_id,
+            "name": name
+        });
+        client.request("Group.SetName", Some(params)).await
+            .context("Failed to set group name")?;
+
+        group.name = name.clone();
+    }
+
+    if let Some(mute) = changes.mute {
+        let params = json!({
+            "id": group_id,
+            "mute": mute
+        });
+        let result = client.request("Group.SetMute", Some(params)).await
+            .context("Failed to set group mute status")?;
+
+        group.muted = result.get("mute").and_then(|m| m.as_bool()).unwrap_or(mute);
+    }
+
+    if let Some(stream_id) = &changes.stream_id {
+        let params = json!({
+            "id": group_id,
+            "stream_id": stream_id
+        });
+        let result = client.request("Group.SetStream", Some(params)).await
+            .context("Failed to set group stream")?;
+
+        group.stream_id = result.get("stream_id").and_then(|s| s.as_str()).unwrap_or(stream_id).to_string();
+    }
+
+    if let Some(clients) = &changes.clients {
+        let params = json!({
+            "id": group_id,
+            "clients": clients
+        });
+        let result = client.request("Group.SetClients", Some(params)).await
+            .context("Failed to set group clients")?;
+
+        // The server answers with its full state; a group left without clients is removed from it.
+        let server_info: Option<Server> = result.get("server").and_then(|s| Server::deserialize(s).ok());
+        group.clients = server_info
+            .and_then(|server_info| server_info.find_group(group_id).map(|g| g.clients.clone()))
+            .unwrap_or_default();
+    }
+
+    Ok(group)
+}
+
+/// Helper function to get all available group IDs and names for debugging
+fn get_available_groups(server_info: &Server) -> Vec<String> {
+    server_info.groups.iter()
+        .map(|g| {
+            let name = if g.name.is_empty() { "unnamed" } else { &g.name };
+            format!("{} ({})", g.id, name)
+        })
+        .collect()
+}
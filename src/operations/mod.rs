@@ -0,0 +1,4 @@
+pub mod client;
+pub mod group;
+pub mod server;
+pub mod stream;
\ No newline at end of file
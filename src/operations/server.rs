@@ -0,0 +1,39 @@
+use anyhow::Result;
+use serde::Serialize;
+use crate::models::{Host, Snapserver};
+use crate::rpc::capabilities::{Capability, Version};
+use crate::rpc::client::SnapcastRpcClient;
+
+/// The server host and software, with the number of objects it manages.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerSummary {
+    pub host: Host,
+    pub snapserver: Snapserver,
+    pub rpc_version: Version,
+    pub capabilities: Vec<Capability>,
+    pub groups: usize,
+    pub clients_connected: usize,
+    pub clients_disconnected: usize,
+    pub streams: usize,
+    pub streams_playing: usize,
+}
+
+/// Describes the server and counts its groups, clients and streams.
+pub async fn get_server(client: &SnapcastRpcClient) -> Result<ServerSummary> {
+    let server_info = client.get_status().await?;
+    let capabilities = client.capabilities().await?;
+
+    let clients_connected = server_info.clients().filter(|c| c.connected).count();
+
+    Ok(ServerSummary {
+        host: server_info.server.host.clone(),
+        snapserver: server_info.server.snapserver.clone(),
+        rpc_version: capabilities.rpc_version,
+        capabilities: capabilities.supported().iter().copied().collect(),
+        groups: server_info.groups.len(),
+        clients_connected,
+        clients_disconnected: server_info.clients().count() - clients_connected,
+        streams: server_info.streams.len(),
+        streams_playing: server_info.streams.iter().filter(|s| s.status == "playing").count(),
+    })
+}
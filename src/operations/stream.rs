@@ -0,0 +1,416 @@
+use std::fmt;
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::models::{Group, Server, Stream};
+use crate::rpc::client::{check_batch_results, SnapcastRpcClient};
+use crate::rpc::transport::{tls, ConnectionOptions};
+
+/// Characters escaped in the location of a stream URI, such as the path of a pipe.
+const LOCATION: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'?');
+
+/// Characters escaped in the query of a stream URI.
+const QUERY_VALUE: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'&').add(b'+').add(b'=');
+
+/// A stream with the groups playing it and the version of the server it lives on.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamDetails {
+    pub stream: Stream,
+    pub groups: Vec<Group>,
+    pub server_version: String,
+}
+
+/// Lists every stream.
+pub async fn list_streams(client: &SnapcastRpcClient) -> Result<Vec<Stream>> {
+    Ok(client.get_status().await?.streams)
+}
+
+/// Finds a stream by ID.
+pub async fn get_stream(client: &SnapcastRpcClient, stream_id: &str) -> Result<StreamDetails> {
+    let server_info = client.get_status().await?;
+
+    let stream = server_info.find_stream(stream_id)
+        .with_context(|| {
+            let available_streams: Vec<String> = get_available_streams(&server_info);
+            format!("Stream with ID '{}' not found. Available streams: {:?}", stream_id, available_streams)
+        })?;
+
+    Ok(StreamDetails {
+        stream: stream.clone(),
+        groups: server_info.groups_for_stream(&stream.id).into_iter().cloned().collect(),
+        server_version: server_info.server.snapserver.version.clone(),
+    })
+}
+
+/// Source of a new stream, turned into the URI passed to `Stream.AddStream`,
+/// e.g. `pipe:///tmp/snapfifo?name=Radio&mode=create`.
+#[derive(Debug, Clone)]
+pub struct StreamSource {
+    scheme: String,
+    location: String,
+    params: Vec<(String, String)>,
+}
+
+impl StreamSource {
+    /// `location` is what follows `scheme://`, such as `/tmp/snapfifo` or `0.0.0.0:4953`.
+    /// The name becomes the ID of the stream.
+    pub fn new(scheme: &str, location: &str, name: &str) -> Self {
+        StreamSource {
+            scheme: scheme.to_string(),
+            location: location.to_string(),
+            params: vec![("name".to_string(), name.to_string())],
+        }
+    }
+
+    /// Adds a query parameter, such as `sampleformat` or `devicename`.
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a query parameter when a value is given.
+    pub fn param_opt(self, key: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.param(key, value),
+            None => self,
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        let query: Vec<String> = self.params.iter()
+            .map(|(key, value)| format!("{}={}", key, utf8_percent_encode(value, QUERY_VALUE)))
+            .collect();
+        format!("{}://{}?{}", self.scheme, utf8_percent_encode(&self.location, LOCATION), query.join("&"))
+    }
+}
+
+/// Creates a stream and returns it.
+pub async fn add_stream(client: &SnapcastRpcClient, source: &StreamSource) -> Result<StreamDetails> {
+    let uri = source.uri();
+    let params = json!({
+        "streamUri": uri
+    });
+    let result = client.request("Stream.AddStream", Some(params)).await
+        .with_context(|| format!("Failed to create stream '{}'", uri))?;
+
+    let stream_id = result.get("stream_id").and_then(|id| id.as_str())
+        .context("Failed to create stream: the server did not return its ID")?;
+    get_stream(client, stream_id).await
+}
+
+/// Removes a stream and returns the server state without it.
+pub async fn remove_stream(client: &SnapcastRpcClient, stream_id: &str) -> Result<Server> {
+    let params = json!({
+        "id": stream_id
+    });
+    client.request("Stream.RemoveStream", Some(params)).await
+        .with_context(|| format!("Failed to remove stream '{}'", stream_id))?;
+
+    client.get_status().await
+}
+
+/// How a stream to control is addressed.
+#[derive(Debug, Clone)]
+pub enum StreamTarget {
+    /// The stream with this ID.
+    Stream(String),
+    /// The stream a group, given by ID or name, is playing.
+    Group(String),
+    /// The stream the group of a client is playing.
+    Client(String),
+}
+
+impl StreamTarget {
+    /// Finds the ID of the addressed stream in the server state.
+    pub fn resolve(&self, server_info: &Server) -> Result<String> {
+        let stream_id = match self {
+            StreamTarget::Stream(stream_id) => stream_id.clone(),
+            StreamTarget::Group(identifier) => server_info.find_group(identifier)
+                .with_context(|| format!("Group with identifier '{}' not found", identifier))?
+                .stream_id.clone(),
+            StreamTarget::Client(client_id) => server_info.group_for_client(client_id)
+                .with_context(|| format!("Client with ID '{}' not found", client_id))?
+                .stream_id.clone(),
+        };
+        if stream_id.is_empty() {
+            return Err(anyhow!("{} is not playing any stream", self));
+        }
+        Ok(stream_id)
+    }
+}
+
+impl fmt::Display for StreamTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamTarget::Stream(stream_id) => write!(f, "stream '{}'", stream_id),
+            StreamTarget::Group(identifier) => write!(f, "group '{}'", identifier),
+            StreamTarget::Client(client_id) => write!(f, "client '{}'", client_id),
+        }
+    }
+}
+
+/// Playback command sent with `Stream.Control`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackCommand {
+    Play,
+    Pause,
+    PlayPause,
+    Stop,
+    Next,
+    Previous,
+    /// Moves the position by this many seconds, backwards when negative.
+    Seek(f64),
+    /// Jumps to this many seconds into the track.
+    SetPosition(f64),
+}
+
+impl PlaybackCommand {
+    /// Name of the command in `Stream.Control`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlaybackCommand::Play => "play",
+            PlaybackCommand::Pause => "pause",
+            PlaybackCommand::PlayPause => "playPause",
+            PlaybackCommand::Stop => "stop",
+            PlaybackCommand::Next => "next",
+            PlaybackCommand::Previous => "previous",
+            PlaybackCommand::Seek(_) => "seek",
+            PlaybackCommand::SetPosition(_) => "setPosition",
+        }
+    }
+
+    /// Stream property telling whether the command is currently possible.
+    fn required_property(&self) -> Option<&'static str> {
+        match self {
+            PlaybackCommand::Play => Some("canPlay"),
+            PlaybackCommand::Pause => Some("canPause"),
+            PlaybackCommand::PlayPause => None,
+            PlaybackCommand::Stop => None,
+            PlaybackCommand::Next => Some("canGoNext"),
+            PlaybackCommand::Previous => Some("canGoPrevious"),
+            PlaybackCommand::Seek(_) | PlaybackCommand::SetPosition(_) => Some("canSeek"),
+        }
+    }
+
+    fn params(&self) -> Option<Value> {
+        match self {
+            PlaybackCommand::Seek(offset) => Some(json!({ "offset": offset })),
+            PlaybackCommand::SetPosition(position) => Some(json!({ "position": position })),
+            _ => None,
+        }
+    }
+}
+
+/// Sends a playback command to the addressed stream and returns the stream.
+///
+/// Fails without sending anything when the stream reports that it cannot be controlled
+/// or does not currently allow the command.
+pub async fn control_stream(client: &SnapcastRpcClient, target: &StreamTarget, command: PlaybackCommand) -> Result<StreamDetails> {
+    let server_info = client.get_status().await?;
+    let stream_id = target.resolve(&server_info)?;
+    let stream = server_info.find_stream(&stream_id)
+        .with_context(|| format!("Stream with ID '{}' not found", stream_id))?;
+
+    if stream.property_flag("canControl") == Some(false) {
+        return Err(anyhow!("Stream '{}' can not be controlled", stream_id));
+    }
+    if let Some(property) = command.required_property() {
+        if stream.property_flag(property) == Some(false) {
+            return Err(anyhow!("Stream '{}' does not allow {} right now ({} is false)", stream_id, command.name(), property));
+        }
+    }
+
+    let mut params = json!({
+        "id": stream_id,
+        "command": command.name()
+    });
+    if let Some(command_params) = command.params() {
+        params["params"] = command_params;
+    }
+    client.request("Stream.Control", Some(params)).await
+        .with_context(|| format!("Failed to send {} to stream '{}'", command.name(), stream_id))?;
+
+    get_stream(client, &stream_id).await
+}
+
+/// Repeat mode of a controllable stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopStatus {
+    /// Play the queue once.
+    None,
+    /// Repeat the current track.
+    Track,
+    /// Repeat the whole playlist.
+    Playlist,
+}
+
+impl LoopStatus {
+    /// Value of the `loopStatus` property.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoopStatus::None => "none",
+            LoopStatus::Track => "track",
+            LoopStatus::Playlist => "playlist",
+        }
+    }
+}
+
+/// Properties to change on a controllable stream. `None` leaves a property untouched.
+#[derive(Debug, Clone, Default)]
+pub struct StreamChanges {
+    pub loop_status: Option<LoopStatus>,
+    pub shuffle: Option<bool>,
+    /// Volume of the player in percent.
+    pub volume: Option<i64>,
+    pub mute: Option<bool>,
+    /// Playback speed, 1.0 being normal.
+    pub rate: Option<f64>,
+}
+
+impl StreamChanges {
+    pub fn is_empty(&self) -> bool {
+        self.loop_status.is_none() && self.shuffle.is_none() && self.volume.is_none() && self.mute.is_none() && self.rate.is_none()
+    }
+
+    /// `Stream.SetProperty` property names and values.
+    fn properties(&self) -> Vec<(&'static str, Value)> {
+        let mut properties = Vec::new();
+        if let Some(loop_status) = self.loop_status {
+            properties.push(("loopStatus", json!(loop_status.as_str())));
+        }
+        if let Some(shuffle) = self.shuffle {
+            properties.push(("shuffle", json!(shuffle)));
+        }
+        if let Some(volume) = self.volume {
+            properties.push(("volume", json!(volume)));
+        }
+        if let Some(mute) = self.mute {
+            properties.push(("mute", json!(mute)));
+        }
+        if let Some(rate) = self.rate {
+            properties.push(("rate", json!(rate)));
+        }
+        properties
+    }
+}
+
+/// Applies `changes` to a stream in a single batch and returns its updated state.
+///
+/// Fails without sending anything when the values are out of range or the stream
+/// reports that it cannot be controlled.
+pub async fn set_stream(client: &SnapcastRpcClient, stream_id: &str, changes: &StreamChanges) -> Result<StreamDetails> {
+    if let Some(volume) = changes.volume {
+        if !(0..=100).contains(&volume) {
+            return Err(anyhow!("Stream volume must be between 0 and 100, got {}", volume));
+        }
+    }
+    if let Some(rate) = changes.rate {
+        if !(rate.is_finite() && rate > 0.0) {
+            return Err(anyhow!("Stream rate must be greater than 0, got {}", rate));
+        }
+    }
+
+    let details = get_stream(client, stream_id).await?;
+    if details.stream.property_flag("canControl") == Some(false) {
+        return Err(anyhow!("Stream '{}' can not be controlled", stream_id));
+    }
+
+    let properties = changes.properties();
+    let labels: Vec<String> = properties.iter()
+        .map(|(property, _)| format!("Failed to set stream {}", property))
+        .collect();
+    let calls = properties.into_iter()
+        .map(|(property, value)| ("Stream.SetProperty", Some(json!({
+            "id": stream_id,
+            "property": property,
+            "value": value
+        }))))
+        .collect();
+
+    let results = client.batch(calls).await?;
+    check_batch_results(results, &labels)?;
+
+    get_stream(client, stream_id).await
+}
+
+/// Cover art of the track a stream plays.
+#[derive(Debug, Clone)]
+pub struct Artwork {
+    pub data: Vec<u8>,
+    /// Image format such as `png` or `jpeg`, when known.
+    pub format: Option<String>,
+}
+
+/// Loads the cover art of the current track, from the image embedded in the metadata or else
+/// by downloading `artUrl`. Returns `None` when the stream publishes neither.
+///
+/// Credentials are only sent along when `artUrl` points at the server itself, such as its image cache.
+pub async fn fetch_artwork(stream: &Stream, server_url: &str, options: &ConnectionOptions) -> Result<Option<Artwork>> {
+    let Some(metadata) = stream.metadata() else {
+        return Ok(None);
+    };
+
+    if let Some(art_data) = metadata.art_data.filter(|art_data| !art_data.data.is_empty()) {
+        let data = STANDARD.decode(art_data.data.trim())
+            .with_context(|| format!("Stream '{}' published invalid base64 artwork", stream.id))?;
+        let format = Some(art_data.extension).filter(|extension| !extension.is_empty());
+        return Ok(Some(Artwork { data, format }));
+    }
+
+    match metadata.art_url.filter(|art_url| !art_url.is_empty()) {
+        Some(art_url) => download_artwork(&art_url, server_url, options).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+async fn download_artwork(art_url: &str, server_url: &str, options: &ConnectionOptions) -> Result<Artwork> {
+    let url = url::Url::parse(art_url).with_context(|| format!("Invalid artwork URL '{}'", art_url))?;
+
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = options.request_timeout {
+        builder = builder.timeout(timeout);
+    }
+    if url.scheme() == "https" {
+        let config = tls::client_config(&options.tls)?;
+        builder = builder.use_preconfigured_tls((*config).clone());
+    }
+    let http = builder.build().context("Failed to create HTTP client")?;
+
+    let mut request = http.get(url.clone());
+    if let Some(credentials) = options.auth.as_ref().filter(|_| same_host(&url, server_url)) {
+        request = request.header(reqwest::header::AUTHORIZATION, credentials.authorization_header());
+    }
+
+    let response = request.send().await
+        .with_context(|| format!("Failed to download artwork from {}", art_url))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("Downloading artwork from {} failed with status {}", art_url, status));
+    }
+
+    let format = response.headers().get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.strip_prefix("image/"))
+        .map(|format| format.split(';').next().unwrap_or(format).trim().to_string());
+    let data = response.bytes().await.context("Failed to read artwork")?.to_vec();
+    Ok(Artwork { data, format })
+}
+
+/// Whether `url` is served by the same host and port as the server at `server_url`.
+fn same_host(url: &url::Url, server_url: &str) -> bool {
+    let Ok(server) = url::Url::parse(server_url) else {
+        return false;
+    };
+    url.host_str() == server.host_str() && url.port_or_known_default() == server.port_or_known_default()
+}
+
+/// Helper function to get all available stream IDs for debugging
+fn get_available_streams(server_info: &Server) -> Vec<String> {
+    server_info.streams.iter().map(|s| s.id.clone()).collect()
+}
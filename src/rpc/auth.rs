@@ -0,0 +1,56 @@
+use std::fmt;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+
+/// Credentials for servers or reverse proxies that require authentication.
+#[derive(Clone)]
+pub enum Credentials {
+    /// HTTP Basic authentication.
+    Basic { username: String, password: String },
+    /// Bearer token authentication.
+    Bearer { token: String },
+}
+
+impl Credentials {
+    /// Value of the `Authorization` header sent on the WebSocket upgrade and HTTP requests.
+    pub fn authorization_header(&self) -> String {
+        match self {
+            Credentials::Basic { .. } => format!("Basic {}", self.param()),
+            Credentials::Bearer { .. } => format!("Bearer {}", self.param()),
+        }
+    }
+
+    /// Parameters of the `Server.Authenticate` call.
+    pub fn authenticate_params(&self) -> Value {
+        let scheme = match self {
+            Credentials::Basic { .. } => "Basic",
+            Credentials::Bearer { .. } => "Bearer",
+        };
+        json!({
+            "scheme": scheme,
+            "param": self.param()
+        })
+    }
+
+    fn param(&self) -> String {
+        match self {
+            Credentials::Basic { username, password } => STANDARD.encode(format!("{}:{}", username, password)),
+            Credentials::Bearer { token } => token.clone(),
+        }
+    }
+}
+
+// Keep secrets out of debug output and logs.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Credentials::Bearer { .. } => f.debug_struct("Bearer").field("token", &"<redacted>").finish(),
+        }
+    }
+}
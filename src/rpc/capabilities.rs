@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// A `major.minor.patch` version, as reported by `Server.GetRPCVersion` or in the snapserver version string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version { major, minor, patch }
+    }
+
+    /// Parses the leading numeric parts of a version string such as `0.28.0` or `0.27.0-beta.1`.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim().trim_start_matches('v').split(['.', '-', '+']);
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+        let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+        Some(Version::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Optional server features that depend on the snapserver release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// `Stream.AddStream` and `Stream.RemoveStream`.
+    StreamManagement,
+    /// `Stream.Control` and `Stream.SetProperty`, introduced with JSON-RPC 2.
+    StreamControl,
+    /// `Server.Authenticate`.
+    Authentication,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [
+        Capability::StreamManagement,
+        Capability::StreamControl,
+        Capability::Authentication,
+    ];
+
+    /// Capability a method depends on, if any.
+    ///
+    /// `Server.Authenticate` is deliberately not gated: the server version cannot be
+    /// queried before authenticating, and a server asking for credentials supports it anyway.
+    pub fn for_method(method: &str) -> Option<Self> {
+        match method {
+            "Stream.AddStream" | "Stream.RemoveStream" => Some(Capability::StreamManagement),
+            "Stream.Control" | "Stream.SetProperty" => Some(Capability::StreamControl),
+            _ => None,
+        }
+    }
+
+    /// Lowest JSON-RPC version providing this capability.
+    fn min_rpc_version(&self) -> Option<Version> {
+        match self {
+            Capability::StreamControl => Some(Version::new(2, 0, 0)),
+            Capability::StreamManagement | Capability::Authentication => None,
+        }
+    }
+
+    /// First snapserver release providing this capability.
+    fn min_server_version(&self) -> Version {
+        match self {
+            Capability::StreamManagement => Version::new(0, 16, 0),
+            Capability::StreamControl => Version::new(0, 26, 0),
+            Capability::Authentication => Version::new(0, 31, 0),
+        }
+    }
+
+    /// Describes the lowest server providing this capability, e.g. `snapserver 0.26.0 or newer (JSON-RPC 2.0.0)`.
+    pub fn requirement(&self) -> String {
+        match self.min_rpc_version() {
+            Some(rpc) => format!("snapserver {} or newer (JSON-RPC {})", self.min_server_version(), rpc),
+            None => format!("snapserver {} or newer", self.min_server_version()),
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Capability::StreamManagement => "stream-management",
+            Capability::StreamControl => "stream-control",
+            Capability::Authentication => "authentication",
+        })
+    }
+}
+
+/// Features supported by the connected server, derived from its versions.
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    pub rpc_version: Version,
+    /// `None` when the snapserver version string could not be parsed.
+    pub server_version: Option<Version>,
+    supported: BTreeSet<Capability>,
+}
+
+impl Capabilities {
+    /// Derives the capability set. An unknown snapserver version is given the benefit of the doubt.
+    pub fn new(rpc_version: Version, server_version: Option<Version>) -> Self {
+        let supported = Capability::ALL.into_iter()
+            .filter(|capability| {
+                capability.min_rpc_version().is_none_or(|min| rpc_version >= min)
+                    && server_version.is_none_or(|version| version >= capability.min_server_version())
+            })
+            .collect();
+
+        Capabilities { rpc_version, server_version, supported }
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.supported.contains(&capability)
+    }
+
+    /// Every supported capability, in a stable order.
+    pub fn supported(&self) -> &BTreeSet<Capability> {
+        &self.supported
+    }
+
+    /// Human-readable versions of the server, e.g. `snapserver 0.28.0 (JSON-RPC 2.0.0)`.
+    pub fn describe_server(&self) -> String {
+        match self.server_version {
+            Some(version) => format!("snapserver {} (JSON-RPC {})", version, self.rpc_version),
+            None => format!("snapserver of unknown version (JSON-RPC {})", self.rpc_version),
+        }
+    }
+}
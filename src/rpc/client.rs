@@ -1,41 +1,555 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use serde_json::{json, Value};
-use anyhow::{Result, Context};
-use crate::utils::websocket::send_websocket_message;
+use anyhow::{Result, Context, anyhow};
+use futures::Stream;
+use tokio::sync::{broadcast, mpsc, oneshot, OnceCell};
+use tokio::sync::broadcast::error::RecvError;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use crate::models::{Client, Group, Server};
+use crate::rpc::auth::Credentials;
+use crate::rpc::capabilities::{Capabilities, Capability, Version};
+use crate::rpc::error::{RpcError, RpcErrorKind, TimeoutError, TransportError};
+use crate::rpc::events::{Event, EventFilter, MissedEvents};
+use crate::rpc::transport::{self, Channels, ConnectionOptions, Incoming, RetryPolicy};
 
+/// Responses awaited by in-flight requests, keyed by JSON-RPC `id`, or why the transport
+/// failed to deliver them. Becomes `None` once the connection is closed.
+type PendingRequests = Arc<Mutex<Option<HashMap<String, oneshot::Sender<Result<Value, String>>>>>>;
+
+/// Why the connection ended, once it has.
+type CloseReason = Arc<Mutex<Option<String>>>;
+
+/// Publishes decoded notifications to subscribers.
+/// Becomes `None` once the connection is closed, which ends every subscription.
+type Notifications = Arc<Mutex<Option<broadcast::Sender<Event>>>>;
+
+/// Capacity of the notification channel before slow subscribers start lagging.
+const NOTIFICATION_CAPACITY: usize = 256;
+
+/// JSON-RPC client holding a single long-lived connection to a Snapcast server.
+///
+/// Responses are matched to their requests by `id`, so any number of requests
+/// can be in flight at once. Messages without an `id` are server notifications
+/// and are published to subscribers instead.
+///
+/// When credentials are configured, calls rejected as unauthorized are retried
+/// once after authenticating with `Server.Authenticate`. Status reads that time
+/// out or fail to reach the server, such as a refused HTTP POST, are retried
+/// according to the connection's [`RetryPolicy`]. Methods that
+/// depend on a [`Capability`] fail early when the server does not provide it.
 pub struct SnapcastRpcClient {
-    server_url: String,
+    sender: mpsc::UnboundedSender<String>,
+    pending: PendingRequests,
+    close_reason: CloseReason,
+    notifications: Notifications,
+    auth: Option<Credentials>,
+    request_timeout: Option<Duration>,
+    retry: RetryPolicy,
+    rpc_version: OnceCell<Version>,
+    /// `None` inside when the snapserver version string could not be parsed.
+    server_version: OnceCell<Option<Version>>,
 }
 
 impl SnapcastRpcClient {
-    pub fn new(server_url: &str) -> Self {
-        SnapcastRpcClient {
-            server_url: server_url.to_string(),
+    /// Connects to the server and starts dispatching incoming messages.
+    ///
+    /// Failed attempts are retried according to `options.retry`.
+    pub async fn connect(server_url: &str, options: &ConnectionOptions) -> Result<Self> {
+        let (sender, receiver) = open(server_url, options).await?;
+        let pending: PendingRequests = Arc::new(Mutex::new(Some(HashMap::new())));
+        let close_reason: CloseReason = Arc::new(Mutex::new(None));
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        let notifications: Notifications = Arc::new(Mutex::new(Some(notifications)));
+
+        tokio::spawn(dispatch_incoming(
+            receiver,
+            pending.clone(),
+            close_reason.clone(),
+            notifications.clone(),
+        ));
+
+        let client = SnapcastRpcClient {
+            sender,
+            pending,
+            close_reason,
+            notifications,
+            auth: options.auth.clone(),
+            request_timeout: options.request_timeout,
+            retry: options.retry,
+            rpc_version: OnceCell::new(),
+            server_version: OnceCell::new(),
+        };
+
+        // Raw TCP has no handshake to carry credentials, so authenticate up front.
+        if server_url.starts_with("tcp://") && client.auth.is_some() {
+            client.authenticate().await?;
+        }
+
+        Ok(client)
+    }
+
+    /// Authenticates the connection with `Server.Authenticate` using the configured credentials.
+    pub async fn authenticate(&self) -> Result<()> {
+        let credentials = self.auth.as_ref().context("No credentials configured")?;
+        self.call("Server.Authenticate", Some(credentials.authenticate_params())).await
+            .context("Failed to authenticate with the server")?;
+        Ok(())
+    }
+
+    /// Fetches the full server state with `Server.GetStatus`.
+    pub async fn get_status(&self) -> Result<Server> {
+        let result = self.read("Server.GetStatus", None).await?;
+        let server: Server = parse_member(&result, "server").context("Failed to get server information from response")?;
+        let _ = self.server_version.set(Version::parse(&server.server.snapserver.version));
+        Ok(server)
+    }
+
+    /// JSON-RPC protocol version of the server, queried once with `Server.GetRPCVersion`.
+    pub async fn rpc_version(&self) -> Result<Version> {
+        self.rpc_version.get_or_try_init(|| async {
+            let result = self.read("Server.GetRPCVersion", None).await?;
+            Version::deserialize(&result).context("Failed to parse the RPC version of the server")
+        }).await.copied()
+    }
+
+    /// Snapserver release, taken from the first `Server.GetStatus` response.
+    ///
+    /// `None` when the server reports a version string that cannot be parsed.
+    pub async fn server_version(&self) -> Result<Option<Version>> {
+        if let Some(version) = self.server_version.get() {
+            return Ok(*version);
+        }
+        let server = self.get_status().await?;
+        Ok(Version::parse(&server.server.snapserver.version))
+    }
+
+    /// Features supported by the server, derived from its cached versions.
+    pub async fn capabilities(&self) -> Result<Capabilities> {
+        Ok(Capabilities::new(self.rpc_version().await?, self.server_version().await?))
+    }
+
+    /// Fails with a helpful message unless the server provides `capability`.
+    pub async fn require(&self, capability: Capability) -> Result<()> {
+        let capabilities = self.capabilities().await?;
+        if capabilities.supports(capability) {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "{} requires {}, but the server runs {}",
+            capability,
+            capability.requirement(),
+            capabilities.describe_server()
+        ))
+    }
+
+    /// Fails early when `method` depends on a capability the server lacks.
+    async fn check_supported(&self, method: &str) -> Result<()> {
+        match Capability::for_method(method) {
+            Some(capability) => self.require(capability).await
+                .with_context(|| format!("{} is not supported by this server", method)),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetches a single client with `Client.GetStatus`.
+    pub async fn get_client_status(&self, client_id: &str) -> Result<Client> {
+        let result = self.read("Client.GetStatus", Some(json!({ "id": client_id }))).await?;
+        parse_member(&result, "client").context("Failed to get client information from response")
+    }
+
+    /// Fetches a single group with `Group.GetStatus`.
+    pub async fn get_group_status(&self, group_id: &str) -> Result<Group> {
+        let result = self.read("Group.GetStatus", Some(json!({ "id": group_id }))).await?;
+        parse_member(&result, "group").context("Failed to get group information from response")
+    }
+
+    /// Calls `method` with optional `params` and returns the `result` member of the response.
+    ///
+    /// A JSON-RPC error response is returned as an [`RpcError`].
+    pub async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        self.check_supported(method).await?;
+        self.call_authenticated(method, params).await
+    }
+
+    /// Sends a single call, authenticating and retrying once if the server asks for credentials.
+    async fn call_authenticated(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        match self.call(method, params.clone()).await {
+            Err(e) if self.auth.is_some() && is_unauthorized(&e) => {
+                self.authenticate().await?;
+                self.call(method, params).await
+            }
+            result => result,
         }
     }
 
-    pub async fn get_status(&self) -> Result<Value> {
-        let message = json!({
-            "id": uuid::Uuid::new_v4().to_string(),
-            "jsonrpc": "2.0",
-            "method": "Server.GetStatus"
-        });
+    /// Calls an idempotent read method, retrying timeouts and transport failures with exponential backoff.
+    async fn read(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let mut attempt = 0;
+        loop {
+            match self.call_authenticated(method, params.clone()).await {
+                Err(e) if attempt < self.retry.retries && is_retryable(&e) => {
+                    attempt += 1;
+                    tokio::time::sleep(self.retry.delay(attempt)).await;
+                }
+                Err(e) if attempt > 0 && is_retryable(&e) => {
+                    return Err(e.context(format!("{} got no response after {} attempts", method, attempt + 1)));
+                }
+                Err(e) if attempt > 0 => return Err(e.context(format!("{} failed on attempt {}", method, attempt + 1))),
+                result => return result,
+            }
+        }
+    }
 
-        let response = send_websocket_message(&self.server_url, message)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to send websocket message: {}", e))?;
+    /// Sends a single call without any retry.
+    async fn call(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let response = self.send_rpc_message(build_message(method, params)).await?;
+        into_result(method, response).map_err(|e| {
+            if is_method_not_found(&e) {
+                e.context(format!("{} is not supported by this server", method))
+            } else {
+                e
+            }
+        })
+    }
 
-        response.get("result")
-            .and_then(|r| r.get("server"))
-            .cloned()
-            .context("Failed to get server information from response")
-            .map(|server| server.clone())
+    /// Sends several calls as a single JSON-RPC batch and waits for all of their responses.
+    ///
+    /// Results are returned in the order of `calls`, each either the `result` member of its
+    /// response or the [`RpcError`] the server reported for that entry.
+    pub async fn batch(&self, calls: Vec<(&str, Option<Value>)>) -> Result<Vec<Result<Value, RpcError>>> {
+        for (method, _) in &calls {
+            self.check_supported(method).await?;
+        }
+
+        let results = self.send_batch(calls.clone()).await?;
+
+        // Only retry when every entry was rejected, so nothing gets applied twice.
+        let all_unauthorized = !results.is_empty() && results.iter()
+            .all(|result| matches!(result, Err(e) if e.kind == RpcErrorKind::Unauthorized));
+        if self.auth.is_some() && all_unauthorized {
+            self.authenticate().await?;
+            return self.send_batch(calls).await;
+        }
+
+        Ok(results)
     }
 
+    /// Sends a batch without any retry.
+    async fn send_batch(&self, calls: Vec<(&str, Option<Value>)>) -> Result<Vec<Result<Value, RpcError>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let methods: Vec<&str> = calls.iter().map(|(method, _)| *method).collect();
+        let messages: Vec<Value> = calls.into_iter()
+            .map(|(method, params)| build_message(method, params))
+            .collect();
+        let ids = messages.iter().map(message_id).collect::<Result<Vec<String>>>()?;
+
+        let receivers = self.register(&ids)?;
+        self.transmit(Value::Array(messages), &ids)?;
+
+        let label = format!("Batch ({})", methods.join(", "));
+        let responses = self.within_timeout(&label, &ids, async {
+            let mut responses = Vec::with_capacity(receivers.len());
+            for receiver in receivers {
+                let response = receiver.await.map_err(|_| self.closed_error())?
+                    .map_err(|reason| TransportError { method: label.clone(), reason })?;
+                responses.push(response);
+            }
+            Ok::<_, anyhow::Error>(responses)
+        }).await??;
+
+        let mut results = Vec::with_capacity(responses.len());
+        for (method, response) in methods.into_iter().zip(responses) {
+            results.push(into_result(method, response).map_err(|e| match e.downcast::<RpcError>() {
+                Ok(rpc_error) => rpc_error,
+                Err(other) => RpcError::malformed(method, &other.to_string()),
+            }));
+        }
+
+        Ok(results)
+    }
+
+    /// Sends a prepared JSON-RPC message and waits for the response carrying the same `id`.
+    ///
+    /// Fails with a [`TimeoutError`] when no response arrives within the request timeout,
+    /// and with a [`TransportError`] when the transport could not deliver the message.
     pub async fn send_rpc_message(&self, message: Value) -> Result<Value> {
-        let response = send_websocket_message(&self.server_url, message)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to send websocket message: {}", e))?;
+        let id = message_id(&message)?;
+        let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("Request").to_string();
+        let ids = [id];
+        let receiver = self.register(&ids)?.remove(0);
+        self.transmit(message, &ids)?;
+
+        self.within_timeout(&method, &ids, receiver).await?
+            .map_err(|_| self.closed_error())?
+            .map_err(|reason| TransportError { method, reason }.into())
+    }
+
+    /// Awaits `future` within the request timeout, unregistering `ids` if it expires.
+    async fn within_timeout<T>(&self, method: &str, ids: &[String], future: impl Future<Output = T>) -> Result<T> {
+        let Some(timeout) = self.request_timeout else {
+            return Ok(future.await);
+        };
+
+        match tokio::time::timeout(timeout, future).await {
+            Ok(output) => Ok(output),
+            Err(_) => {
+                self.unregister(ids);
+                Err(TimeoutError { method: method.to_string(), timeout }.into())
+            }
+        }
+    }
+
+    /// Describes a connection that ended while a request was waiting for its response.
+    fn closed_error(&self) -> anyhow::Error {
+        match self.close_reason.lock().unwrap().as_deref() {
+            Some(reason) => anyhow!("Connection closed before a response was received: {}", reason),
+            None => anyhow!("Connection closed before a response was received"),
+        }
+    }
+
+    /// Registers the given request ids as awaiting a response.
+    fn register(&self, ids: &[String]) -> Result<Vec<oneshot::Receiver<Result<Value, String>>>> {
+        let mut guard = self.pending.lock().unwrap();
+        let pending = guard.as_mut().context("Failed to send message: connection closed")?;
+
+        Ok(ids.iter()
+            .map(|id| {
+                let (response_tx, response_rx) = oneshot::channel();
+                pending.insert(id.clone(), response_tx);
+                response_rx
+            })
+            .collect())
+    }
+
+    /// Stops waiting for responses to the given request ids.
+    fn unregister(&self, ids: &[String]) {
+        if let Some(pending) = self.pending.lock().unwrap().as_mut() {
+            for id in ids {
+                pending.remove(id);
+            }
+        }
+    }
+
+    /// Writes a message to the connection, unregistering `ids` if that fails.
+    fn transmit(&self, payload: Value, ids: &[String]) -> Result<()> {
+        if self.sender.send(payload.to_string()).is_err() {
+            self.unregister(ids);
+            return Err(anyhow!("Failed to send message: connection closed"));
+        }
+        Ok(())
+    }
+
+    /// Subscribes to notifications pushed by the server.
+    ///
+    /// The receiver is closed once the connection ends.
+    pub fn notifications(&self) -> broadcast::Receiver<Event> {
+        match self.notifications.lock().unwrap().as_ref() {
+            Some(sender) => sender.subscribe(),
+            // The sender is dropped right away, so the receiver reports a closed channel.
+            None => broadcast::channel(1).1,
+        }
+    }
+
+    /// Subscribes to the events matching `filter` as an async stream.
+    ///
+    /// The stream ends when the connection closes. A subscriber that falls too far behind
+    /// receives [`MissedEvents`] before the stream resumes with the latest events.
+    pub fn events(&self, filter: EventFilter) -> impl Stream<Item = Result<Event, MissedEvents>> {
+        futures::stream::unfold((self.notifications(), filter), |(mut receiver, filter)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if filter.matches(&event) => return Some((Ok(event), (receiver, filter))),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => return Some((Err(MissedEvents(missed)), (receiver, filter))),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+/// Opens the transport for `server_url`, retrying attempts that timed out or hit a network
+/// error with exponential backoff. TLS, authentication and configuration errors fail at once.
+async fn open(server_url: &str, options: &ConnectionOptions) -> Result<Channels> {
+    let mut attempt = 0;
+    loop {
+        let result = match options.connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, transport::connect(server_url, options)).await
+                .unwrap_or_else(|_| Err(TimeoutError { method: format!("Connecting to {}", server_url), timeout }.into())),
+            None => transport::connect(server_url, options).await,
+        };
+
+        match result {
+            Err(e) if attempt < options.retry.retries && is_transient(&e) => {
+                attempt += 1;
+                tokio::time::sleep(options.retry.delay(attempt)).await;
+            }
+            Err(e) if attempt > 0 => return Err(e.context(format!("Failed to connect after {} attempts", attempt + 1))),
+            result => return result,
+        }
+    }
+}
+
+/// Turns per-entry batch results into their values, or one error describing every failed entry.
+///
+/// `labels` describe each call in the same order and prefix the reported failures.
+pub fn check_batch_results(results: Vec<Result<Value, RpcError>>, labels: &[String]) -> Result<Vec<Value>> {
+    let total = results.len();
+    let mut values = Vec::with_capacity(total);
+    let mut failures = Vec::new();
+
+    for (result, label) in results.into_iter().zip(labels) {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => failures.push(format!("{}: {}", label, error)),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(values)
+    } else {
+        Err(anyhow!("{} of {} batched calls failed:\n  {}", failures.len(), total, failures.join("\n  ")))
+    }
+}
 
-        Ok(response)
+/// Whether a failed connection attempt may succeed later: it timed out, or the network
+/// refused, reset or could not route the connection.
+fn is_transient(error: &anyhow::Error) -> bool {
+    error.is::<TimeoutError>() || error.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|e| matches!(
+            e.kind(),
+            ErrorKind::ConnectionRefused | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected | ErrorKind::BrokenPipe | ErrorKind::TimedOut
+                | ErrorKind::UnexpectedEof | ErrorKind::HostUnreachable | ErrorKind::NetworkUnreachable
+        ))
+}
+
+/// Whether a failed read may succeed when sent again: it timed out or never reached the server.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    error.is::<TimeoutError>() || error.is::<TransportError>()
+}
+
+/// Whether an error is the server rejecting an unauthenticated call.
+fn is_unauthorized(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<RpcError>()
+        .is_some_and(|e| e.kind == RpcErrorKind::Unauthorized)
+}
+
+/// Whether an error is the server rejecting an unknown method.
+fn is_method_not_found(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<RpcError>()
+        .is_some_and(|e| e.kind == RpcErrorKind::MethodNotFound)
+}
+
+/// Builds a JSON-RPC request object with a fresh `id`.
+fn build_message(method: &str, params: Option<Value>) -> Value {
+    let mut message = json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "jsonrpc": "2.0",
+        "method": method
+    });
+    if let Some(params) = params {
+        message["params"] = params;
     }
-}
\ No newline at end of file
+    message
+}
+
+/// Returns the string `id` of a request object.
+fn message_id(message: &Value) -> Result<String> {
+    message.get("id")
+        .and_then(|id| id.as_str())
+        .map(|id| id.to_string())
+        .context("JSON-RPC message has no string id")
+}
+
+/// Extracts the `result` of a response to `method`, turning an `error` member into an [`RpcError`].
+fn into_result(method: &str, mut response: Value) -> Result<Value> {
+    if let Some(error) = response.get("error") {
+        return Err(RpcError::from_response(method, error).into());
+    }
+
+    response.get_mut("result")
+        .map(Value::take)
+        .with_context(|| format!("{} response has neither result nor error", method))
+}
+
+/// Deserializes the `<key>` member of a result.
+fn parse_member<T: DeserializeOwned>(result: &Value, key: &str) -> Result<T> {
+    let value = result.get(key)
+        .with_context(|| format!("Result has no '{}' member", key))?;
+
+    Ok(T::deserialize(value)?)
+}
+
+/// Routes every incoming message to the request awaiting it or to notification subscribers.
+async fn dispatch_incoming(
+    mut receiver: mpsc::UnboundedReceiver<Result<Incoming>>,
+    pending: PendingRequests,
+    close_reason: CloseReason,
+    notifications: Notifications,
+) {
+    while let Some(incoming) = receiver.recv().await {
+        let text = match incoming {
+            Ok(Incoming::Message(text)) => text,
+            Ok(Incoming::Failed { ids, reason }) => {
+                if let Some(pending) = pending.lock().unwrap().as_mut() {
+                    for id in ids {
+                        if let Some(response_tx) = pending.remove(&id) {
+                            let _ = response_tx.send(Err(reason.clone()));
+                        }
+                    }
+                }
+                continue;
+            }
+            Err(e) => {
+                *close_reason.lock().unwrap() = Some(format!("{:#}", e));
+                break;
+            }
+        };
+        let message: Value = match serde_json::from_str(&text) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+
+        match message {
+            Value::Array(entries) => {
+                for entry in entries {
+                    dispatch_message(entry, &pending, &notifications);
+                }
+            }
+            message => dispatch_message(message, &pending, &notifications),
+        }
+    }
+
+    // Dropping the senders wakes every waiting request with a "connection closed" error
+    // and ends every event subscription.
+    pending.lock().unwrap().take();
+    notifications.lock().unwrap().take();
+}
+
+fn dispatch_message(
+    message: Value,
+    pending: &PendingRequests,
+    notifications: &Notifications,
+) {
+    if let Some(id) = message.get("id").and_then(|id| id.as_str()) {
+        let response_tx = pending.lock().unwrap().as_mut().and_then(|pending| pending.remove(id));
+        if let Some(response_tx) = response_tx {
+            let _ = response_tx.send(Ok(message));
+        }
+    } else if let Some(event) = Event::from_notification(&message) {
+        if let Some(sender) = notifications.lock().unwrap().as_ref() {
+            // Sending only fails when nobody is subscribed, which is fine.
+            let _ = sender.send(event);
+        }
+    }
+}
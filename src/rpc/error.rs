@@ -0,0 +1,147 @@
+use std::fmt;
+use std::time::Duration;
+use serde_json::Value;
+
+/// Category of a JSON-RPC error, derived from its numeric code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorKind {
+    /// -32700: the server could not parse the request.
+    ParseError,
+    /// -32600: the request is not a valid JSON-RPC object.
+    InvalidRequest,
+    /// -32601: the method does not exist on this server.
+    MethodNotFound,
+    /// -32602: the parameters are missing or malformed.
+    InvalidParams,
+    /// -32603: snapserver uses this for unknown clients, groups and streams too.
+    InternalError,
+    /// 401: the server requires authentication.
+    Unauthorized,
+    /// 403: the authenticated user may not call this method.
+    Forbidden,
+    /// -32000 to -32099: implementation-defined server errors.
+    ServerError,
+    /// Any other code.
+    Unknown,
+}
+
+impl RpcErrorKind {
+    /// Classifies a JSON-RPC error code.
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            -32700 => RpcErrorKind::ParseError,
+            -32600 => RpcErrorKind::InvalidRequest,
+            -32601 => RpcErrorKind::MethodNotFound,
+            -32602 => RpcErrorKind::InvalidParams,
+            -32603 => RpcErrorKind::InternalError,
+            401 => RpcErrorKind::Unauthorized,
+            403 => RpcErrorKind::Forbidden,
+            -32099..=-32000 => RpcErrorKind::ServerError,
+            _ => RpcErrorKind::Unknown,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            RpcErrorKind::ParseError => "parse error",
+            RpcErrorKind::InvalidRequest => "invalid request",
+            RpcErrorKind::MethodNotFound => "method not found",
+            RpcErrorKind::InvalidParams => "invalid params",
+            RpcErrorKind::InternalError => "internal error",
+            RpcErrorKind::Unauthorized => "unauthorized",
+            RpcErrorKind::Forbidden => "forbidden",
+            RpcErrorKind::ServerError => "server error",
+            RpcErrorKind::Unknown => "unknown error",
+        }
+    }
+}
+
+/// Error object returned by the server for a failed JSON-RPC call.
+#[derive(Debug, Clone)]
+pub struct RpcError {
+    pub kind: RpcErrorKind,
+    pub code: i64,
+    pub message: String,
+    pub method: String,
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    /// Builds an error from the `error` member of a response to `method`.
+    pub fn from_response(method: &str, error: &Value) -> Self {
+        let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+        let message = error.get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("no message")
+            .to_string();
+
+        RpcError {
+            kind: RpcErrorKind::from_code(code),
+            code,
+            message,
+            method: method.to_string(),
+            data: error.get("data").cloned(),
+        }
+    }
+
+    /// Builds an error for a response that carries neither a result nor an error.
+    pub fn malformed(method: &str, message: &str) -> Self {
+        RpcError {
+            kind: RpcErrorKind::InvalidRequest,
+            code: 0,
+            message: message.to_string(),
+            method: method.to_string(),
+            data: None,
+        }
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed: {} ({}, code {})",
+            self.method,
+            self.message,
+            self.kind.description(),
+            self.code
+        )?;
+        if let Some(data) = &self.data {
+            write!(f, ": {}", data)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// A call that got no response within the request timeout.
+#[derive(Debug, Clone)]
+pub struct TimeoutError {
+    pub method: String,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} timed out after {:?} without a response", self.method, self.timeout)
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
+/// A call the transport failed to deliver, such as an HTTP POST that was refused or answered
+/// with an error status. Unlike a closed connection, later calls may still succeed.
+#[derive(Debug, Clone)]
+pub struct TransportError {
+    pub method: String,
+    pub reason: String,
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} could not reach the server: {}", self.method, self.reason)
+    }
+}
+
+impl std::error::Error for TransportError {}
@@ -0,0 +1,128 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::models::client::Volume;
+use crate::models::{Client, Server, Stream};
+
+/// A notification pushed by the server, decoded from its `method` and `params`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params")]
+pub enum Event {
+    #[serde(rename = "Client.OnConnect")]
+    ClientConnected { id: String, client: Client },
+    #[serde(rename = "Client.OnDisconnect")]
+    ClientDisconnected { id: String, client: Client },
+    #[serde(rename = "Client.OnVolumeChanged")]
+    ClientVolumeChanged { id: String, volume: Volume },
+    #[serde(rename = "Client.OnLatencyChanged")]
+    ClientLatencyChanged { id: String, latency: i64 },
+    #[serde(rename = "Client.OnNameChanged")]
+    ClientNameChanged { id: String, name: String },
+    #[serde(rename = "Group.OnMute")]
+    GroupMuteChanged { id: String, mute: bool },
+    #[serde(rename = "Group.OnStreamChanged")]
+    GroupStreamChanged { id: String, stream_id: String },
+    #[serde(rename = "Group.OnNameChanged")]
+    GroupNameChanged { id: String, name: String },
+    #[serde(rename = "Stream.OnUpdate")]
+    StreamUpdated { id: String, stream: Stream },
+    #[serde(rename = "Stream.OnProperties")]
+    StreamPropertiesChanged { id: String, properties: Value },
+    #[serde(rename = "Server.OnUpdate")]
+    ServerUpdated { server: Server },
+    /// A notification this version does not know, or one whose params did not match.
+    #[serde(untagged)]
+    Other {
+        method: String,
+        #[serde(default)]
+        params: Value,
+    },
+}
+
+impl Event {
+    /// Decodes a JSON-RPC notification. Returns `None` for messages without a `method`.
+    pub fn from_notification(notification: &Value) -> Option<Self> {
+        notification.get("method")?;
+        Event::deserialize(notification).ok()
+    }
+
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::ClientConnected { .. } => EventKind::ClientConnect,
+            Event::ClientDisconnected { .. } => EventKind::ClientDisconnect,
+            Event::ClientVolumeChanged { .. } => EventKind::ClientVolume,
+            Event::ClientLatencyChanged { .. } => EventKind::ClientLatency,
+            Event::ClientNameChanged { .. } => EventKind::ClientName,
+            Event::GroupMuteChanged { .. } => EventKind::GroupMute,
+            Event::GroupStreamChanged { .. } => EventKind::GroupStream,
+            Event::GroupNameChanged { .. } => EventKind::GroupName,
+            Event::StreamUpdated { .. } => EventKind::StreamUpdate,
+            Event::StreamPropertiesChanged { .. } => EventKind::StreamProperties,
+            Event::ServerUpdated { .. } => EventKind::ServerUpdate,
+            Event::Other { .. } => EventKind::Other,
+        }
+    }
+
+    /// Id of the client, group or stream the event is about, if any.
+    pub fn object_id(&self) -> Option<&str> {
+        match self {
+            Event::ClientConnected { id, .. }
+            | Event::ClientDisconnected { id, .. }
+            | Event::ClientVolumeChanged { id, .. }
+            | Event::ClientLatencyChanged { id, .. }
+            | Event::ClientNameChanged { id, .. }
+            | Event::GroupMuteChanged { id, .. }
+            | Event::GroupStreamChanged { id, .. }
+            | Event::GroupNameChanged { id, .. }
+            | Event::StreamUpdated { id, .. }
+            | Event::StreamPropertiesChanged { id, .. } => Some(id),
+            Event::ServerUpdated { .. } => None,
+            Event::Other { params, .. } => params.get("id").and_then(|id| id.as_str()),
+        }
+    }
+}
+
+/// Category of an [`Event`], used to filter subscriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    ClientConnect,
+    ClientDisconnect,
+    ClientVolume,
+    ClientLatency,
+    ClientName,
+    GroupMute,
+    GroupStream,
+    GroupName,
+    StreamUpdate,
+    StreamProperties,
+    ServerUpdate,
+    /// Notifications not covered by the other kinds.
+    Other,
+}
+
+/// Selects the events a subscriber receives. Empty lists match everything.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub kinds: Vec<EventKind>,
+    /// Client, group or stream ids. Events not about a specific object never match a non-empty list.
+    pub ids: Vec<String>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&event.kind()))
+            && (self.ids.is_empty() || event.object_id().is_some_and(|id| self.ids.iter().any(|wanted| wanted == id)))
+    }
+}
+
+/// Reported to a subscriber that fell too far behind and skipped some events.
+#[derive(Debug, Clone, Copy)]
+pub struct MissedEvents(pub u64);
+
+impl fmt::Display for MissedEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missed {} notifications because the subscriber fell behind", self.0)
+    }
+}
+
+impl std::error::Error for MissedEvents {}
@@ -0,0 +1,232 @@
+use std::sync::{Arc, Mutex, RwLock};
+use anyhow::Result;
+use futures::StreamExt;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use crate::models::Server;
+use crate::rpc::client::SnapcastRpcClient;
+use crate::rpc::events::{Event, EventFilter};
+
+/// Function called after every change to the mirrored state.
+type ChangeCallback = Arc<dyn Fn(&Event, &Server) + Send + Sync>;
+
+type Callbacks = Arc<Mutex<Vec<ChangeCallback>>>;
+
+/// Local copy of the server state, loaded once with `Server.GetStatus` and then kept
+/// current by applying the notifications pushed by the server.
+///
+/// When a notification cannot be applied, such as a new client connecting, the full status
+/// is fetched again and the notification is reported with it. Events missed by a slow
+/// subscriber are replaced by the fetched status, reported as [`Event::ServerUpdated`].
+/// The mirror stops updating once the connection closes.
+pub struct ServerMirror {
+    state: Arc<RwLock<Server>>,
+    callbacks: Callbacks,
+    live: watch::Receiver<bool>,
+    task: JoinHandle<()>,
+}
+
+impl ServerMirror {
+    /// Loads the current state and starts following notifications.
+    pub async fn start(client: Arc<SnapcastRpcClient>) -> Result<Self> {
+        Self::follow(client, Vec::new()).await
+    }
+
+    /// Like [`start`](Self::start), with `callback` registered before the first notification
+    /// is applied, so that it sees every change.
+    pub async fn start_with(
+        client: Arc<SnapcastRpcClient>,
+        callback: impl Fn(&Event, &Server) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        Self::follow(client, vec![Arc::new(callback)]).await
+    }
+
+    async fn follow(client: Arc<SnapcastRpcClient>, callbacks: Vec<ChangeCallback>) -> Result<Self> {
+        // Subscribe first so nothing that happens while the status loads is lost.
+        let mut events = Box::pin(client.events(EventFilter::default()));
+        let state = Arc::new(RwLock::new(client.get_status().await?));
+        let callbacks: Callbacks = Arc::new(Mutex::new(callbacks));
+        let (live_tx, live) = watch::channel(true);
+
+        let task = {
+            let state = state.clone();
+            let callbacks = callbacks.clone();
+            tokio::spawn(async move {
+                while let Some(event) = events.next().await {
+                    let event = match event {
+                        Ok(event) if apply(&mut state.write().unwrap(), &event) => event,
+                        // Reload the state the event refers to, and still report the event itself.
+                        Ok(event) => match client.get_status().await {
+                            Ok(server) => {
+                                *state.write().unwrap() = server;
+                                event
+                            }
+                            Err(_) => break,
+                        },
+                        // Events were missed, only the reloaded state tells what changed.
+                        Err(_) => match client.get_status().await {
+                            Ok(server) => {
+                                *state.write().unwrap() = server.clone();
+                                Event::ServerUpdated { server }
+                            }
+                            Err(_) => break,
+                        },
+                    };
+
+                    // Run the callbacks without holding either lock, so they may register more callbacks.
+                    let server = state.read().unwrap().clone();
+                    let callbacks = callbacks.lock().unwrap().clone();
+                    for callback in &callbacks {
+                        callback(&event, &server);
+                    }
+                }
+                let _ = live_tx.send(false);
+            })
+        };
+
+        Ok(ServerMirror { state, callbacks, live, task })
+    }
+
+    /// Returns a copy of the current state.
+    pub fn snapshot(&self) -> Server {
+        self.state.read().unwrap().clone()
+    }
+
+    /// Runs `f` against the current state without copying it.
+    pub fn read<R>(&self, f: impl FnOnce(&Server) -> R) -> R {
+        f(&self.state.read().unwrap())
+    }
+
+    /// Registers a callback run after each change, with the event and the updated state.
+    /// Changes applied before the call are not reported, see [`start_with`](Self::start_with).
+    pub fn on_change(&self, callback: impl Fn(&Event, &Server) + Send + Sync + 'static) {
+        self.callbacks.lock().unwrap().push(Arc::new(callback));
+    }
+
+    /// Whether the mirror is still following the server.
+    pub fn is_live(&self) -> bool {
+        *self.live.borrow()
+    }
+
+    /// Waits until the mirror stops following the server, usually because the connection closed.
+    pub async fn closed(&self) {
+        let mut live = self.live.clone();
+        let _ = live.wait_for(|live| !live).await;
+    }
+}
+
+impl Drop for ServerMirror {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Applies a notification to `server`. Returns `false` when it refers to unknown objects
+/// and the state must be fetched again.
+fn apply(server: &mut Server, event: &Event) -> bool {
+    match event {
+        Event::ClientConnected { id, client } | Event::ClientDisconnected { id, client } => {
+            match server.find_client_mut(id) {
+                Some(existing) => *existing = client.clone(),
+                // A new client gets a new group, which only a full status describes.
+                None => return false,
+            }
+        }
+        Event::ClientVolumeChanged { id, volume } => match server.find_client_mut(id) {
+            Some(client) => client.config.volume = volume.clone(),
+            None => return false,
+        },
+        Event::ClientLatencyChanged { id, latency } => match server.find_client_mut(id) {
+            Some(client) => client.config.latency = *latency,
+            None => return false,
+        },
+        Event::ClientNameChanged { id, name } => match server.find_client_mut(id) {
+            Some(client) => client.config.name = name.clone(),
+            None => return false,
+        },
+        Event::GroupMuteChanged { id, mute } => match server.find_group_mut(id) {
+            Some(group) => group.muted = *mute,
+            None => return false,
+        },
+        Event::GroupStreamChanged { id, stream_id } => match server.find_group_mut(id) {
+            Some(group) => group.stream_id = stream_id.clone(),
+            None => return false,
+        },
+        Event::GroupNameChanged { id, name } => match server.find_group_mut(id) {
+            Some(group) => group.name = name.clone(),
+            None => return false,
+        },
+        Event::StreamUpdated { id, stream } => match server.find_stream_mut(id) {
+            Some(existing) => *existing = stream.clone(),
+            None => server.streams.push(stream.clone()),
+        },
+        Event::StreamPropertiesChanged { id, properties } => match server.find_stream_mut(id) {
+            Some(stream) => stream.properties = Some(properties.clone()),
+            None => return false,
+        },
+        Event::ServerUpdated { server: updated } => *server = updated.clone(),
+        Event::Other { .. } => {}
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Client, Group, Stream, Volume};
+
+    fn client(id: &str, percent: i64) -> Client {
+        let mut client = Client { id: id.to_string(), connected: true, ..Default::default() };
+        client.config.volume.percent = percent;
+        client
+    }
+
+    fn stream(id: &str, status: &str) -> Stream {
+        Stream { id: id.to_string(), status: status.to_string(), ..Default::default() }
+    }
+
+    fn server() -> Server {
+        Server {
+            groups: vec![Group { id: "g1".to_string(), clients: vec![client("c1", 40)], ..Default::default() }],
+            streams: vec![stream("default", "idle")],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn applies_volume_of_known_client() {
+        let mut server = server();
+        let volume = Volume { muted: true, percent: 70 };
+        assert!(apply(&mut server, &Event::ClientVolumeChanged { id: "c1".to_string(), volume }));
+        let volume = &server.find_client("c1").unwrap().config.volume;
+        assert_eq!((volume.muted, volume.percent), (true, 70));
+    }
+
+    #[test]
+    fn unknown_client_needs_reload() {
+        let mut server = server();
+        let volume = Volume { muted: false, percent: 70 };
+        assert!(!apply(&mut server, &Event::ClientVolumeChanged { id: "c9".to_string(), volume }));
+        assert!(!apply(&mut server, &Event::ClientConnected { id: "c9".to_string(), client: client("c9", 50) }));
+        assert!(server.find_client("c9").is_none());
+    }
+
+    #[test]
+    fn replaces_known_client_on_disconnect() {
+        let mut server = server();
+        let mut disconnected = client("c1", 55);
+        disconnected.connected = false;
+        assert!(apply(&mut server, &Event::ClientDisconnected { id: "c1".to_string(), client: disconnected }));
+        let client = server.find_client("c1").unwrap();
+        assert_eq!((client.connected, client.config.volume.percent), (false, 55));
+    }
+
+    #[test]
+    fn stream_update_inserts_new_stream() {
+        let mut server = server();
+        assert!(apply(&mut server, &Event::StreamUpdated { id: "radio".to_string(), stream: stream("radio", "playing") }));
+        assert!(apply(&mut server, &Event::StreamUpdated { id: "default".to_string(), stream: stream("default", "playing") }));
+        let streams: Vec<_> = server.streams.iter().map(|s| (s.id.as_str(), s.status.as_str())).collect();
+        assert_eq!(streams, [("default", "playing"), ("radio", "playing")]);
+    }
+}
@@ -1 +1,7 @@
-pub mod client;
\ No newline at end of file
+pub mod auth;
+pub mod capabilities;
+pub mod client;
+pub mod error;
+pub mod events;
+pub mod mirror;
+pub mod transport;
\ No newline at end of file
@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use super::{tls, Channels, ConnectionOptions, Incoming};
+
+/// Bridges HTTP POSTs to the server's `/jsonrpc` endpoint to a pair of text channels.
+///
+/// There is no handshake: each string pushed into the returned sender is posted as
+/// its own request, and the response body is forwarded to the returned receiver.
+/// A failed POST is reported as [`Incoming::Failed`] for the requests it carried, so
+/// later requests still go through. Requests are sent concurrently. Server
+/// notifications are never received over HTTP.
+pub async fn connect_http(url: &str, secure: bool, options: &ConnectionOptions) -> Result<Channels> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if secure {
+        let config = tls::client_config(&options.tls)?;
+        builder = builder.use_preconfigured_tls((*config).clone());
+    }
+    let http = builder.build().context("Failed to create HTTP client")?;
+    let url = url.to_string();
+    let authorization = options.auth.as_ref().map(|credentials| credentials.authorization_header());
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Result<Incoming>>();
+
+    tokio::spawn(async move {
+        while let Some(body) = outgoing_rx.recv().await {
+            let http = http.clone();
+            let url = url.clone();
+            let authorization = authorization.clone();
+            let incoming_tx = incoming_tx.clone();
+            tokio::spawn(async move {
+                let ids = request_ids(&body);
+                let incoming = match post(&http, &url, authorization.as_deref(), body).await {
+                    Ok(text) => Incoming::Message(text),
+                    Err(e) => Incoming::Failed { ids, reason: format!("{:#}", e) },
+                };
+                let _ = incoming_tx.send(Ok(incoming));
+            });
+        }
+    });
+
+    Ok((outgoing_tx, incoming_rx))
+}
+
+/// Posts one JSON-RPC payload and returns the response body.
+async fn post(http: &reqwest::Client, url: &str, authorization: Option<&str>, body: String) -> Result<String> {
+    let mut request = http.post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body);
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to send HTTP request to {}", url))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("HTTP request to {} failed with status {}", url, status));
+    }
+
+    response.text().await.context("Failed to read HTTP response body")
+}
+
+/// The ids of the requests in a JSON-RPC payload, a single request or a batch.
+fn request_ids(body: &str) -> Vec<String> {
+    let requests = match serde_json::from_str(body) {
+        Ok(Value::Array(requests)) => requests,
+        Ok(request) => vec![request],
+        Err(_) => Vec::new(),
+    };
+    requests.iter()
+        .filter_map(|request| request.get("id").and_then(Value::as_str).map(str::to_string))
+        .collect()
+}
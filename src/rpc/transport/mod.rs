@@ -0,0 +1,120 @@
+pub mod endpoint;
+pub mod http;
+pub mod tcp;
+pub mod tls;
+pub mod websocket;
+
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use tokio::sync::mpsc;
+use tls::TlsOptions;
+use crate::rpc::auth::Credentials;
+
+/// Text channels bridged to an open connection: frames to send and what was received.
+///
+/// A received `Err` reports why the connection failed and is the last item before the receiver closes.
+pub type Channels = (mpsc::UnboundedSender<String>, mpsc::UnboundedReceiver<Result<Incoming>>);
+
+/// Something a transport received for the client.
+#[derive(Debug)]
+pub enum Incoming {
+    /// A frame sent by the server.
+    Message(String),
+    /// The requests with these ids could not be delivered, but the connection remains usable,
+    /// such as an HTTP POST that was refused or answered with an error status.
+    Failed { ids: Vec<String>, reason: String },
+}
+
+/// Wire protocol used to reach the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// JSON-RPC over WebSocket.
+    Ws,
+    /// Newline-delimited JSON-RPC over raw TCP.
+    Tcp,
+    /// One HTTP POST to /jsonrpc per request, without notifications.
+    Http,
+}
+
+/// Settings applied when opening a connection.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionOptions {
+    pub tls: TlsOptions,
+    pub auth: Option<Credentials>,
+    /// Time allowed to establish the connection, `None` to wait indefinitely.
+    pub connect_timeout: Option<Duration>,
+    /// Time allowed for each response, `None` to wait indefinitely.
+    pub request_timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+}
+
+/// How failed connection attempts and idempotent reads are retried.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetryPolicy {
+    /// Attempts made after the first one fails.
+    pub retries: u32,
+    /// Delay before the first retry, doubled after every further attempt.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Longest delay between two attempts.
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    /// Delay before retry number `attempt`, counting from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(Self::MAX_BACKOFF)
+    }
+}
+
+impl Transport {
+    /// Port snapserver listens on for this transport by default.
+    pub fn default_port(&self, secure: bool) -> u16 {
+        match (self, secure) {
+            (Transport::Tcp, _) => 1705,
+            (_, false) => 1780,
+            (_, true) => 1788,
+        }
+    }
+
+    /// URL scheme identifying this transport, with or without TLS.
+    pub fn scheme(&self, secure: bool) -> &'static str {
+        match (self, secure) {
+            (Transport::Ws, false) => "ws",
+            (Transport::Ws, true) => "wss",
+            (Transport::Tcp, _) => "tcp",
+            (Transport::Http, false) => "http",
+            (Transport::Http, true) => "https",
+        }
+    }
+
+    /// Looks up the transport for a URL scheme and whether the scheme implies TLS.
+    pub fn from_scheme(scheme: &str) -> Option<(Self, bool)> {
+        match scheme.to_lowercase().as_str() {
+            "ws" => Some((Transport::Ws, false)),
+            "wss" => Some((Transport::Ws, true)),
+            "tcp" => Some((Transport::Tcp, false)),
+            "http" => Some((Transport::Http, false)),
+            "https" => Some((Transport::Http, true)),
+            _ => None,
+        }
+    }
+}
+
+/// Opens a connection to `url` with the transport selected by its scheme.
+pub async fn connect(url: &str, options: &ConnectionOptions) -> Result<Channels> {
+    let (scheme, rest) = url.split_once("://")
+        .ok_or_else(|| anyhow!("Server URL '{}' has no scheme", url))?;
+
+    match Transport::from_scheme(scheme) {
+        Some((Transport::Ws, secure)) => websocket::connect_websocket(url, secure, options).await,
+        Some((Transport::Tcp, _)) => {
+            let address = rest.split('/').next().unwrap_or(rest);
+            tcp::connect_tcp(address).await
+        }
+        Some((Transport::Http, secure)) => http::connect_http(url, secure, options).await,
+        None => Err(anyhow!("Unsupported transport scheme '{}' in server URL '{}'", scheme, url)),
+    }
+}
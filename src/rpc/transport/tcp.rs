@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use super::{Channels, Incoming};
+
+/// Opens a raw TCP connection and bridges its newline-delimited messages to a pair of text channels.
+///
+/// Each string pushed into the returned sender is written as one line, and every
+/// non-empty line read from the socket is forwarded to the returned receiver. The
+/// receiver is closed once the server closes the connection or an I/O error occurs.
+pub async fn connect_tcp(address: &str) -> Result<Channels> {
+    let stream = TcpStream::connect(address)
+        .await
+        .with_context(|| format!("Failed to connect to {}", address))?;
+    let (read, mut write) = stream.into_split();
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Result<Incoming>>();
+
+    tokio::spawn(async move {
+        while let Some(text) = outgoing_rx.recv().await {
+            let line = format!("{}\r\n", text);
+            if write.write_all(line.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+        let _ = write.shutdown().await;
+    });
+
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(read).lines();
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if incoming_tx.send(Ok(Incoming::Message(line.to_string()))).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = incoming_tx.send(Err(anyhow::Error::new(e).context("Failed to read from TCP connection")));
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok((outgoing_tx, incoming_rx))
+}
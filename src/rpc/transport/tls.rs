@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{anyhow, Context, Result};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+
+/// TLS settings for `wss://` and `https://` connections.
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// PEM bundle of extra CA certificates to trust, in addition to the system store.
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate chain presented to the server.
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key matching `client_cert`.
+    pub client_key: Option<PathBuf>,
+    /// Accept any server certificate. Only meant for testing.
+    pub insecure: bool,
+}
+
+/// Builds the rustls client configuration described by `options`.
+pub fn client_config(options: &TlsOptions) -> Result<Arc<ClientConfig>> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .context("Failed to configure TLS protocol versions")?;
+
+    let builder = if options.insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+    } else {
+        builder.with_root_certificates(root_store(options)?)
+    };
+
+    let config = match (&options.client_cert, &options.client_key) {
+        (Some(cert_path), Some(key_path)) => {
+            let certs = CertificateDer::pem_file_iter(cert_path)
+                .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+                .with_context(|| format!("Failed to read client certificate {}", cert_path.display()))?;
+            let key = PrivateKeyDer::from_pem_file(key_path)
+                .with_context(|| format!("Failed to read client key {}", key_path.display()))?;
+            builder
+                .with_client_auth_cert(certs, key)
+                .context("Invalid client certificate or key")?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => return Err(anyhow!("--client-cert and --client-key must be given together")),
+    };
+
+    Ok(Arc::new(config))
+}
+
+/// Loads the system trust store plus the optional custom CA bundle.
+fn root_store(options: &TlsOptions) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+
+    // Certificates the platform fails to parse are skipped, like most TLS clients do.
+    let native = rustls_native_certs::load_native_certs();
+    roots.add_parsable_certificates(native.certs);
+
+    if let Some(ca_path) = &options.ca_cert {
+        let certs = CertificateDer::pem_file_iter(ca_path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .with_context(|| format!("Failed to read CA certificate {}", ca_path.display()))?;
+        if certs.is_empty() {
+            return Err(anyhow!("No certificate found in {}", ca_path.display()));
+        }
+        for cert in certs {
+            roots.add(cert)
+                .with_context(|| format!("Invalid CA certificate in {}", ca_path.display()))?;
+        }
+    }
+
+    Ok(roots)
+}
+
+/// Certificate verifier used by `--insecure`: any certificate is accepted, but
+/// handshake signatures are still checked so the session itself is well-formed.
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async_tls_with_config, Connector};
+use futures::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use super::{tls, Channels, ConnectionOptions, Incoming};
+
+/// Opens a WebSocket connection and bridges it to a pair of text channels.
+///
+/// Frames pushed into the returned sender are written to the socket, and every
+/// text frame read from the socket is forwarded to the returned receiver. The
+/// receiver is closed once the server closes the connection or an I/O error occurs.
+pub async fn connect_websocket(url: &str, secure: bool, options: &ConnectionOptions) -> Result<Channels> {
+    let connector = if secure {
+        Some(Connector::Rustls(tls::client_config(&options.tls)?))
+    } else {
+        None
+    };
+
+    let mut request = url.into_client_request()
+        .with_context(|| format!("Invalid WebSocket URL {}", url))?;
+    if let Some(credentials) = &options.auth {
+        let header = HeaderValue::from_str(&credentials.authorization_header())
+            .context("Credentials contain characters not allowed in an HTTP header")?;
+        request.headers_mut().insert("Authorization", header);
+    }
+
+    let (ws_stream, _) = connect_async_tls_with_config(request, None, false, connector)
+        .await
+        .with_context(|| format!("Failed to connect to {}", url))?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Result<Incoming>>();
+
+    tokio::spawn(async move {
+        while let Some(text) = outgoing_rx.recv().await {
+            if write.send(Message::Text(text.into())).await.is_err() {
+                return;
+            }
+        }
+        let _ = write.close().await;
+    });
+
+    tokio::spawn(async move {
+        while let Some(msg) = read.next().await {
+            match msg {
+                Ok(Message::Text(text)) => {
+                    if incoming_tx.send(Ok(Incoming::Message(text.to_string()))).is_err() {
+                        break;
+                    }
+                }
+                Ok(Message::Close(_)) => break,
+                Err(e) => {
+                    let _ = incoming_tx.send(Err(anyhow::Error::new(e).context("Failed to read from WebSocket")));
+                    break;
+                }
+                Ok(_) => continue,
+            }
+        }
+    });
+
+    Ok((outgoing_tx, incoming_rx))
+}
@@ -1,37 +1,303 @@
 use std::cmp;
+use anyhow::Result;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-pub fn print_table(headers: Vec<&str>, data: Vec<Vec<String>>) {
-    if data.is_empty() {
-        println!("No data to display.");
-        return;
+/// ANSI escape codes used to highlight changed cells and color status values.
+const HIGHLIGHT: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Columns are not narrowed below this width, or their header's, to fit the terminal.
+const MIN_COLUMN_WIDTH: usize = 8;
+
+/// Rows to print under a header line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Printed instead of the table when there are no rows.
+    pub empty_message: &'static str,
+}
+
+/// How a table is laid out.
+#[derive(Debug, Clone, Copy)]
+pub struct TableStyle {
+    pub headers: bool,
+    /// Color status values and highlight changed cells with ANSI escape codes.
+    pub color: bool,
+    /// Width to fit the table in, usually that of the terminal. Cells that do not fit
+    /// are wrapped at spaces, like lists of clients, or truncated.
+    pub max_width: Option<usize>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<&str>, rows: Vec<Vec<String>>) -> Self {
+        let headers = headers.into_iter().map(String::from).collect();
+        Table { headers, rows, empty_message: "No data to display." }
+    }
+
+    /// Replaces the message printed when there are no rows.
+    pub fn empty_message(mut self, message: &'static str) -> Self {
+        self.empty_message = message;
+        self
     }
 
-    let mut column_widths = headers.iter().map(|h| h.len()).collect::<Vec<usize>>();
+    /// Formats the table, highlighting the cells that differ from `previous` when colors are enabled.
+    ///
+    /// Rows are matched by their first cell, or by position when it is empty.
+    /// Rows missing from `previous` are highlighted entirely.
+    pub fn render(&self, previous: Option<&Table>, style: &TableStyle) -> String {
+        if self.rows.is_empty() {
+            return format!("{}\n", self.empty_message);
+        }
 
-    for row in &data {
-        for (i, cell) in row.iter().enumerate() {
-            if i < column_widths.len() {
-                column_widths[i] = cmp::max(column_widths[i], cell.len());
+        let header_widths = self.headers.iter()
+            .map(|h| if style.headers { h.width() } else { 0 })
+            .collect::<Vec<usize>>();
+        let mut column_widths = header_widths.clone();
+
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                if i < column_widths.len() {
+                    column_widths[i] = cmp::max(column_widths[i], cell.width());
+                }
             }
         }
+
+        if let Some(max_width) = style.max_width {
+            let minimums = column_widths.iter().zip(&header_widths)
+                .map(|(&width, &header)| cmp::min(width, cmp::max(header, MIN_COLUMN_WIDTH)))
+                .collect::<Vec<usize>>();
+            shrink_to_fit(&mut column_widths, &minimums, max_width);
+        }
+
+        let mut output = String::new();
+
+        if style.headers {
+            let header_line = self.headers
+                .iter()
+                .enumerate()
+                .map(|(i, h)| pad(&truncate(h, column_widths[i]), column_widths[i]))
+                .collect::<Vec<String>>()
+                .join("  ");
+            output.push_str(&header_line);
+            output.push('\n');
+        }
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let previous_row = previous.map(|previous| previous.matching_row(index, row));
+            let cells = row.iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let changed = previous_row.is_some_and(|previous_row| previous_row.and_then(|r| r.get(i)) != Some(c));
+                    let color = if !style.color {
+                        None
+                    } else if changed {
+                        Some(HIGHLIGHT)
+                    } else {
+                        self.headers.get(i).and_then(|header| status_color(header, c))
+                    };
+                    (fit(c, column_widths[i]), color)
+                })
+                .collect::<Vec<_>>();
+
+            // A row takes as many lines as its most wrapped cell.
+            let height = cells.iter().map(|(lines, _)| lines.len()).max().unwrap_or(1);
+            for line in 0..height {
+                let row_line = cells
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (lines, color))| {
+                        let cell = pad(lines.get(line).map(String::as_str).unwrap_or_default(), column_widths[i]);
+                        match color {
+                            Some(color) => format!("{}{}{}", color, cell, RESET),
+                            None => cell,
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("  ");
+                output.push_str(&row_line);
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    /// Formats the table as CSV, with or without a header record.
+    pub fn to_csv(&self, headers: bool) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        if headers {
+            writer.write_record(&self.headers)?;
+        }
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+
+    /// The row of this table corresponding to `row`, found at `index` in another table.
+    fn matching_row(&self, index: usize, row: &[String]) -> Option<&Vec<String>> {
+        match row.first() {
+            Some(key) if !key.is_empty() => self.rows.iter().find(|r| r.first() == Some(key)),
+            _ => self.rows.get(index),
+        }
+    }
+}
+
+/// Color of a status value, e.g. green for connected clients and yellow for muted ones.
+fn status_color(header: &str, cell: &str) -> Option<&'static str> {
+    match (header, cell) {
+        ("STATUS", "connected" | "playing") => Some(GREEN),
+        ("STATUS", "disconnected" | "disabled") => Some(RED),
+        ("STATUS", "muted") | ("MUTED", "true") => Some(YELLOW),
+        ("PLAYBACK", "playing") => Some(GREEN),
+        ("PLAYBACK", "paused") => Some(YELLOW),
+        _ => None,
+    }
+}
+
+/// Narrows the widest columns, down to their minimum, until the table fits in `max_width`.
+fn shrink_to_fit(widths: &mut [usize], minimums: &[usize], max_width: usize) {
+    let separators = 2 * widths.len().saturating_sub(1);
+    let available = max_width.saturating_sub(separators);
+    while widths.iter().sum::<usize>() > available {
+        let widest = (0..widths.len())
+            .filter(|&i| widths[i] > minimums[i])
+            .max_by_key(|&i| widths[i]);
+        match widest {
+            Some(i) => widths[i] -= 1,
+            // Too many columns for the terminal, let the lines overflow.
+            None => break,
+        }
     }
+}
+
+/// Pads `text` with spaces to `width` terminal columns.
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
+/// Splits a cell into lines of at most `width` columns. Text with spaces, such as a list
+/// of clients, is wrapped between words; anything else is truncated with an ellipsis.
+fn fit(cell: &str, width: usize) -> Vec<String> {
+    if cell.width() <= width || !cell.contains(' ') {
+        return vec![truncate(cell, width)];
+    }
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in cell.split(' ') {
+        if !line.is_empty() && line.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines.into_iter().map(|line| truncate(&line, width)).collect()
+}
+
+/// Cuts `text` to `width` columns, ending with `…` when anything was left out.
+fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        used += char_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let header_line = headers
-        .iter()
-        .enumerate()
-        .map(|(i, h)| format!("{:<width$}", h, width = column_widths[i]))
-        .collect::<Vec<String>>()
-        .join("  ");
+    const PLAIN: TableStyle = TableStyle { headers: true, color: false, max_width: None };
 
-    println!("{}", header_line);
+    fn table(headers: Vec<&str>, rows: &[&[&str]]) -> Table {
+        Table::new(headers, rows.iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect())
+    }
+
+    #[test]
+    fn aligns_columns_by_display_width() {
+        let table = table(vec!["NAME", "VOL"], &[&["Küche 🎵", "40"], &["pi", "70"]]);
+        assert_eq!(table.render(None, &PLAIN), "NAME      VOL\nKüche 🎵  40 \npi        70 \n");
+    }
+
+    #[test]
+    fn truncates_at_character_boundaries() {
+        assert_eq!(truncate("Living room", 11), "Living room");
+        assert_eq!(truncate("Living room", 7), "Living…");
+        // A wide character that would straddle the limit is left out entirely.
+        assert_eq!(truncate("日本語テキスト", 6), "日本…");
+        assert_eq!(truncate("Küche 🎵", 7), "Küche …");
+    }
+
+    #[test]
+    fn wraps_cells_with_spaces_and_truncates_others() {
+        assert_eq!(fit("c1, c2, c3", 6), ["c1,", "c2, c3"]);
+        assert_eq!(fit("c1, c2, c3", 10), ["c1, c2, c3"]);
+        assert_eq!(fit("librespot:///usr/bin/librespot", 10), ["librespot…"]);
+    }
+
+    #[test]
+    fn wrapped_rows_take_several_lines() {
+        let table = table(vec!["GROUP ID", "CLIENT NAMES"], &[&["g1", "Kitchen, Office, Garage"]]);
+        let style = TableStyle { max_width: Some(24), ..PLAIN };
+        assert_eq!(
+            table.render(None, &style),
+            "GROUP ID  CLIENT NAMES  \ng1        Kitchen,      \n          Office, Garage\n"
+        );
+    }
+
+    #[test]
+    fn shrinks_widest_columns_first_down_to_their_minimum() {
+        let mut widths = [20, 30];
+        shrink_to_fit(&mut widths, &[8, 12], 40);
+        assert_eq!(widths, [19, 19]);
+
+        let mut widths = [20, 30];
+        shrink_to_fit(&mut widths, &[8, 12], 10);
+        assert_eq!(widths, [8, 12]);
+    }
+
+    #[test]
+    fn columns_keep_their_header_or_minimum_width() {
+        let long = "x".repeat(40);
+        let table = table(vec!["ID", "CLIENT NAMES", "URI"], &[&[&long, &long, &long]]);
+        let style = TableStyle { max_width: Some(10), ..PLAIN };
+        let rendered = table.render(None, &style);
+        let header = rendered.lines().next().unwrap();
+        // ID keeps MIN_COLUMN_WIDTH, CLIENT NAMES its header, and URI MIN_COLUMN_WIDTH.
+        assert_eq!(header, format!("{:<8}  CLIENT NAMES  {:<8}", "ID", "URI"));
+        assert_eq!(rendered.lines().nth(1).unwrap().width(), 8 + 2 + 12 + 2 + 8);
+    }
+
+    #[test]
+    fn highlights_changed_cells_only_with_color() {
+        let previous = table(vec!["ID", "VOL"], &[&["c1", "40"]]);
+        let current = table(vec!["ID", "VOL"], &[&["c1", "50"]]);
+        let colored = TableStyle { color: true, ..PLAIN };
+        assert!(current.render(Some(&previous), &colored).contains(&format!("{}50 {}", HIGHLIGHT, RESET)));
+        assert!(!current.render(Some(&previous), &PLAIN).contains('\x1b'));
+    }
 
-    for row in &data {
-        let row_line = row
-            .iter()
-            .enumerate()
-            .map(|(i, c)| format!("{:<width$}", c, width = column_widths[i]))
-            .collect::<Vec<String>>()
-            .join("  ");
-        println!("{}", row_line);
+    #[test]
+    fn prints_the_empty_message_without_rows() {
+        let table = Table::new(vec!["ID"], Vec::new()).empty_message("No clients found.");
+        assert_eq!(table.render(None, &PLAIN), "No clients found.\n");
     }
 }
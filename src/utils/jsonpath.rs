@@ -0,0 +1,388 @@
+use std::cmp::Ordering;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// One step of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// `.name` or `['name']`
+    Field(String),
+    /// `[2]`, or `[-1]` counting from the end
+    Index(i64),
+    /// `[*]` or `.*`, every element of an array or every value of an object
+    Wildcard,
+}
+
+/// A kubectl-style JSONPath such as `.config.volume.percent` or `.clients[*].id`.
+///
+/// Supports fields, quoted fields, array indices and wildcards. The leading `$` and
+/// surrounding braces (`{.id}`) are optional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let trimmed = path.trim();
+        let trimmed = trimmed.strip_prefix('{').and_then(|p| p.strip_suffix('}')).unwrap_or(trimmed).trim();
+        let mut rest = trimmed.strip_prefix('$').unwrap_or(trimmed);
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(|| anyhow!("Unclosed '[' in JSONPath '{}'", path))?;
+                let inner = after[..end].trim();
+                segments.push(if inner == "*" {
+                    Segment::Wildcard
+                } else if let Some(name) = unquote(inner) {
+                    Segment::Field(name.to_string())
+                } else {
+                    Segment::Index(inner.parse().map_err(|_| anyhow!("Invalid index '{}' in JSONPath '{}'", inner, path))?)
+                });
+                rest = &after[end + 1..];
+            } else if let Some(after) = rest.strip_prefix('.') {
+                if after.starts_with('.') {
+                    return Err(anyhow!("Recursive descent (..) is not supported in JSONPath '{}'", path));
+                }
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                match &after[..end] {
+                    "" => {}
+                    "*" => segments.push(Segment::Wildcard),
+                    name => segments.push(Segment::Field(name.to_string())),
+                }
+                rest = &after[end..];
+            } else {
+                return Err(anyhow!("JSONPath '{}' must start with '.', e.g. .config.name", path));
+            }
+        }
+
+        Ok(JsonPath { segments })
+    }
+
+    /// Every value the path leads to in `root`. Missing fields select nothing.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            current = current.into_iter()
+                .flat_map(|value| -> Vec<&'a Value> {
+                    match (segment, value) {
+                        (Segment::Field(name), Value::Object(map)) => map.get(name).into_iter().collect(),
+                        (Segment::Index(index), Value::Array(items)) => {
+                            let index = if *index < 0 { items.len() as i64 + index } else { *index };
+                            usize::try_from(index).ok().and_then(|i| items.get(i)).into_iter().collect()
+                        }
+                        (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                        (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+        current
+    }
+
+    /// The selected values as text, joined with `separator`.
+    pub fn select_text(&self, root: &Value, separator: &str) -> Option<String> {
+        let values = self.select(root);
+        if values.is_empty() {
+            return None;
+        }
+        Some(values.into_iter().map(value_text).collect::<Vec<_>>().join(separator))
+    }
+}
+
+/// A quoted field name such as `'name'` or `"name"`, without its quotes.
+fn unquote(value: &str) -> Option<&str> {
+    ['\'', '"'].into_iter()
+        .find_map(|quote| value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)))
+}
+
+/// Strings without quotes, other values as compact JSON.
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Orders values for `--sort-by`: numbers numerically, everything else by its text.
+pub fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => {
+            a.as_f64().unwrap_or_default().total_cmp(&b.as_f64().unwrap_or_default())
+        }
+        (Some(a), Some(b)) => value_text(a).cmp(&value_text(b)),
+        // Items without the field go last.
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Part of a [`JsonPathTemplate`].
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Path(JsonPath),
+    /// `{range .[*]}...{end}`, renders the body once for every selected value.
+    Range(JsonPath, Vec<Node>),
+}
+
+/// A kubectl-style JSONPath template such as `{range .[*]}{.id}{"\t"}{.config.name}{"\n"}{end}`.
+///
+/// Text outside braces is printed as is, `{"..."}` prints a string literal with `\n`
+/// and `\t` escapes, and an expression selecting several values prints them separated by spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPathTemplate {
+    nodes: Vec<Node>,
+}
+
+impl JsonPathTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        // Stack of open ranges, the template itself at the bottom.
+        let mut stack: Vec<(Option<JsonPath>, Vec<Node>)> = vec![(None, Vec::new())];
+        let mut rest = template;
+
+        while !rest.is_empty() {
+            let Some(start) = rest.find('{') else {
+                push_text(&mut stack, rest);
+                break;
+            };
+            push_text(&mut stack, &rest[..start]);
+
+            let end = closing_brace(&rest[start..])
+                .ok_or_else(|| anyhow!("Unclosed '{{' in JSONPath template '{}'", template))?;
+            let expression = rest[start + 1..start + end].trim();
+            rest = &rest[start + end + 1..];
+
+            if let Some(path) = expression.strip_prefix("range ") {
+                stack.push((Some(JsonPath::parse(path)?), Vec::new()));
+            } else if expression == "end" {
+                let (path, body) = stack.pop().filter(|_| !stack.is_empty())
+                    .ok_or_else(|| anyhow!("'{{end}}' without '{{range}}' in JSONPath template '{}'", template))?;
+                let range = Node::Range(path.expect("only ranges are pushed"), body);
+                stack.last_mut().expect("the template is never popped").1.push(range);
+            } else if let Some(literal) = unquote(expression) {
+                push_text(&mut stack, &unescape(literal));
+            } else {
+                let path = JsonPath::parse(expression)?;
+                stack.last_mut().expect("the template is never popped").1.push(Node::Path(path));
+            }
+        }
+
+        match stack.pop() {
+            Some((None, nodes)) if stack.is_empty() => Ok(JsonPathTemplate { nodes }),
+            _ => Err(anyhow!("'{{range}}' without '{{end}}' in JSONPath template '{}'", template)),
+        }
+    }
+
+    pub fn render(&self, root: &Value) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, root, &mut output);
+        output
+    }
+}
+
+fn push_text(stack: &mut [(Option<JsonPath>, Vec<Node>)], text: &str) {
+    if !text.is_empty() {
+        stack.last_mut().expect("the template is never popped").1.push(Node::Text(text.to_string()));
+    }
+}
+
+/// Position of the brace closing the one `text` starts with, skipping quoted text.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(_), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '}') => return Some(i),
+            _ => {}
+        }
+        escaped = false;
+    }
+    None
+}
+
+fn unescape(literal: &str) -> String {
+    let mut output = String::new();
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('r') => output.push('\r'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+fn render_nodes(nodes: &[Node], root: &Value, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Path(path) => output.push_str(&path.select_text(root, " ").unwrap_or_default()),
+            Node::Range(path, body) => {
+                for value in path.select(root) {
+                    render_nodes(body, value, output);
+                }
+            }
+        }
+    }
+}
+
+/// A line template such as `{{.id}} is at {{.config.volume.percent}}%`, rendered once for
+/// every item of a list, or once for a single object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemTemplate {
+    parts: Vec<(String, Option<JsonPath>)>,
+}
+
+impl ItemTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let end = rest[start..].find("}}")
+                .ok_or_else(|| anyhow!("Unclosed '{{{{' in template '{}'", template))?;
+            let path = JsonPath::parse(&rest[start + 2..start + end])?;
+            parts.push((unescape(&rest[..start]), Some(path)));
+            rest = &rest[start + end + 2..];
+        }
+        parts.push((unescape(rest), None));
+        Ok(ItemTemplate { parts })
+    }
+
+    pub fn render(&self, item: &Value) -> String {
+        self.parts.iter()
+            .map(|(text, path)| {
+                let value = path.as_ref().and_then(|path| path.select_text(item, ",")).unwrap_or_default();
+                format!("{}{}", text, value)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!([
+            { "id": "c1", "config": { "name": "Kitchen", "volume": { "percent": 40 } }, "tags": ["a", "b"] },
+            { "id": "c2", "config": { "name": "Office", "volume": { "percent": 70 } }, "tags": [] },
+        ])
+    }
+
+    fn texts(path: &str, root: &Value) -> Vec<String> {
+        JsonPath::parse(path).unwrap().select(root).into_iter().map(value_text).collect()
+    }
+
+    #[test]
+    fn selects_fields_and_indices() {
+        let root = sample();
+        assert_eq!(texts(".[0].config.name", &root), ["Kitchen"]);
+        assert_eq!(texts("$[1]['config'].volume.percent", &root), ["70"]);
+        assert_eq!(texts("{.[0].tags[1]}", &root), ["b"]);
+        assert_eq!(texts(".[0][\"id\"]", &root), ["c1"]);
+    }
+
+    #[test]
+    fn negative_indices_count_from_the_end() {
+        let root = sample();
+        assert_eq!(texts(".[-1].id", &root), ["c2"]);
+        assert_eq!(texts(".[0].tags[-2]", &root), ["a"]);
+        assert!(texts(".[-3].id", &root).is_empty());
+    }
+
+    #[test]
+    fn missing_fields_select_nothing() {
+        let root = sample();
+        assert!(texts(".[0].config.latency", &root).is_empty());
+        assert!(texts(".[5].id", &root).is_empty());
+        assert_eq!(JsonPath::parse(".[0].nope").unwrap().select_text(&root, ","), None);
+    }
+
+    #[test]
+    fn wildcards_select_every_element() {
+        let root = sample();
+        assert_eq!(texts(".[*].id", &root), ["c1", "c2"]);
+        assert_eq!(texts(".*.config.volume.percent", &root), ["40", "70"]);
+        assert_eq!(texts(".[0].config.volume.*", &root), ["40"]);
+        assert_eq!(JsonPath::parse("[*].tags[*]").unwrap().select_text(&root, ","), Some("a,b".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        assert!(JsonPath::parse("config.name").is_err());
+        assert!(JsonPath::parse(".tags[0").is_err());
+        assert!(JsonPath::parse(".tags[x]").is_err());
+        assert!(JsonPath::parse("..id").is_err());
+    }
+
+    #[test]
+    fn renders_templates_with_literals() {
+        let template = JsonPathTemplate::parse(r#"{range .[*]}{.id}{"\t"}{.config.name}{"\n"}{end}"#).unwrap();
+        assert_eq!(template.render(&sample()), "c1\tKitchen\nc2\tOffice\n");
+
+        let template = JsonPathTemplate::parse("ids: {.[*].id}").unwrap();
+        assert_eq!(template.render(&sample()), "ids: c1 c2");
+    }
+
+    #[test]
+    fn renders_nested_ranges() {
+        let template = JsonPathTemplate::parse(r#"{range .[*]}{.id}:{range .tags[*]} {.}{end};{end}"#).unwrap();
+        assert_eq!(template.render(&sample()), "c1: a b;c2:;");
+    }
+
+    #[test]
+    fn quoted_braces_do_not_close_expressions() {
+        let template = JsonPathTemplate::parse(r#"{"{"}{.[0].id}{'}'}{"\"}\""}"#).unwrap();
+        assert_eq!(template.render(&sample()), "{c1}\"}\"");
+    }
+
+    #[test]
+    fn rejects_unbalanced_templates() {
+        assert!(JsonPathTemplate::parse("{.id").is_err());
+        assert!(JsonPathTemplate::parse("{range .[*]}{.id}").is_err());
+        assert!(JsonPathTemplate::parse("{.id}{end}").is_err());
+        assert!(JsonPathTemplate::parse("{range .[*]}{range .tags[*]}{end}").is_err());
+        assert!(JsonPathTemplate::parse(r#"{"unclosed}"#).is_err());
+    }
+
+    #[test]
+    fn renders_item_templates() {
+        let template = ItemTemplate::parse(r"{{.id}} is at {{.config.volume.percent}}%\n").unwrap();
+        assert_eq!(template.render(&sample()[0]), "c1 is at 40%\n");
+        assert_eq!(template.render(&json!({})), " is at %\n");
+        assert!(ItemTemplate::parse("{{.id").is_err());
+    }
+
+    #[test]
+    fn compares_numbers_numerically_and_missing_values_last() {
+        let (two, ten) = (json!(2), json!(10));
+        assert_eq!(compare_values(Some(&two), Some(&ten)), Ordering::Less);
+
+        let (two, ten) = (json!("2"), json!("10"));
+        assert_eq!(compare_values(Some(&two), Some(&ten)), Ordering::Greater);
+
+        let (a, b) = (json!("alpha"), json!("beta"));
+        assert_eq!(compare_values(Some(&a), Some(&b)), Ordering::Less);
+        assert_eq!(compare_values(Some(&b), None), Ordering::Less);
+        assert_eq!(compare_values(None, Some(&a)), Ordering::Greater);
+        assert_eq!(compare_values(None, None), Ordering::Equal);
+    }
+}
@@ -1,2 +1,3 @@
-pub mod websocket;
-pub mod display;
\ No newline at end of file
+pub mod display;
+pub mod jsonpath;
+pub mod output;
\ No newline at end of file
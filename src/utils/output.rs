@@ -0,0 +1,216 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::Value;
+use crate::utils::display::{Table, TableStyle};
+use crate::utils::jsonpath::{compare_values, ItemTemplate, JsonPath, JsonPathTemplate};
+
+/// Output format of the get, set and delete commands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputFormat {
+    /// Aligned columns with the most useful fields.
+    Table,
+    /// Aligned columns with every field.
+    Wide,
+    /// Full objects as pretty-printed JSON.
+    Json,
+    /// Full objects as YAML.
+    Yaml,
+    /// The wide columns as comma-separated values.
+    Csv,
+    /// `custom-columns=HEADER:PATH,...`, one row per item.
+    CustomColumns(Vec<(String, JsonPath)>),
+    /// `jsonpath=TEMPLATE`, rendered once against the full output.
+    JsonPath(JsonPathTemplate),
+    /// `template=TEMPLATE`, rendered once per item.
+    Template(ItemTemplate),
+}
+
+impl OutputFormat {
+    /// Parses the value of `-o/--output`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let (name, argument) = match value.split_once('=') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (value, None),
+        };
+        match (name, argument) {
+            ("table", None) => Ok(OutputFormat::Table),
+            ("wide", None) => Ok(OutputFormat::Wide),
+            ("json", None) => Ok(OutputFormat::Json),
+            ("yaml", None) => Ok(OutputFormat::Yaml),
+            ("csv", None) => Ok(OutputFormat::Csv),
+            ("custom-columns", Some(spec)) => Ok(OutputFormat::CustomColumns(parse_columns(spec)?)),
+            ("jsonpath", Some(template)) => Ok(OutputFormat::JsonPath(JsonPathTemplate::parse(template)?)),
+            ("template", Some(template)) => Ok(OutputFormat::Template(ItemTemplate::parse(template)?)),
+            _ => Err(anyhow!(
+                "Unknown output format '{}', use table, wide, json, yaml, csv, custom-columns=..., jsonpath=... or template=...",
+                value
+            )),
+        }
+    }
+
+    /// Whether the format prints aligned columns, which can be highlighted and separated by blank lines.
+    pub fn is_table(&self) -> bool {
+        matches!(self, OutputFormat::Table | OutputFormat::Wide | OutputFormat::CustomColumns(_))
+    }
+}
+
+/// Parses `NAME:.config.name,VOL:.config.volume.percent`.
+fn parse_columns(spec: &str) -> Result<Vec<(String, JsonPath)>> {
+    spec.split(',')
+        .map(|column| {
+            let (header, path) = column.split_once(':')
+                .ok_or_else(|| anyhow!("Invalid custom column '{}', expected HEADER:PATH", column))?;
+            Ok((header.trim().to_string(), JsonPath::parse(path)?))
+        })
+        .collect()
+}
+
+/// How to print the result of a command, from `-o/--output`, `--sort-by`, `--no-headers` and `--color`.
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub sort_by: Option<JsonPath>,
+    pub no_headers: bool,
+    pub color: bool,
+    /// Terminal width that tables are fitted to, `None` to never wrap or truncate cells.
+    pub max_width: Option<usize>,
+}
+
+/// Result of a command: the full object for machine-readable formats, and the tables showing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    data: Value,
+    table: Table,
+    /// Table with additional columns for `-o wide` and `-o csv`, when there is one.
+    wide: Option<Table>,
+}
+
+impl Output {
+    pub fn new(data: &impl Serialize, table: Table) -> Result<Self> {
+        Ok(Output { data: serde_json::to_value(data)?, table, wide: None })
+    }
+
+    /// Adds the table shown with `-o wide` and `-o csv`.
+    pub fn with_wide(mut self, wide: Table) -> Self {
+        self.wide = Some(wide);
+        self
+    }
+
+    fn wide_table(&self) -> &Table {
+        self.wide.as_ref().unwrap_or(&self.table)
+    }
+
+    /// The objects shown as rows: the elements of a list, or the single object.
+    fn items(&self) -> Vec<&Value> {
+        match &self.data {
+            Value::Array(items) => items.iter().collect(),
+            single => vec![single],
+        }
+    }
+
+    /// Sorts a list by the value at `path`, keeping the table rows in the same order.
+    fn sorted(&self, path: &JsonPath) -> Output {
+        let Value::Array(items) = &self.data else {
+            return self.clone();
+        };
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by(|&a, &b| compare_values(path.select(&items[a]).first().copied(), path.select(&items[b]).first().copied()));
+
+        let reorder_table = |table: &Table| {
+            let mut table = table.clone();
+            // Only tables with one row per item can follow the new order.
+            if table.rows.len() == items.len() {
+                table.rows = order.iter().map(|&i| table.rows[i].clone()).collect();
+            }
+            table
+        };
+        Output {
+            data: Value::Array(order.iter().map(|&i| items[i].clone()).collect()),
+            table: reorder_table(&self.table),
+            wide: self.wide.as_ref().map(reorder_table),
+        }
+    }
+
+    /// Table with the columns of `-o custom-columns`.
+    fn custom_table(&self, columns: &[(String, JsonPath)]) -> Table {
+        let rows = self.items().into_iter()
+            .map(|item| {
+                columns.iter()
+                    .map(|(_, path)| path.select_text(item, ",").unwrap_or_else(|| "<none>".to_string()))
+                    .collect()
+            })
+            .collect();
+        Table {
+            headers: columns.iter().map(|(header, _)| header.clone()).collect(),
+            rows,
+            empty_message: self.table.empty_message,
+        }
+    }
+
+    /// Formats the output. In the table formats, cells that differ from `previous` are highlighted when colors are enabled.
+    pub fn render(&self, options: &OutputOptions, previous: Option<&Output>) -> Result<String> {
+        if let Some(path) = &options.sort_by {
+            let previous = previous.map(|previous| previous.sorted(path));
+            return self.sorted(path).render_sorted(options, previous.as_ref());
+        }
+        self.render_sorted(options, previous)
+    }
+
+    fn render_sorted(&self, options: &OutputOptions, previous: Option<&Output>) -> Result<String> {
+        let headers = !options.no_headers;
+        let style = TableStyle { headers, color: options.color, max_width: options.max_width };
+        Ok(match &options.format {
+            OutputFormat::Table => self.table.render(previous.map(|p| &p.table), &style),
+            OutputFormat::Wide => self.wide_table().render(previous.map(|p| p.wide_table()), &style),
+            OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(&self.data)?),
+            OutputFormat::Yaml => serde_norway::to_string(&self.data)?,
+            OutputFormat::Csv => self.wide_table().to_csv(headers)?,
+            OutputFormat::CustomColumns(columns) => {
+                let previous = previous.map(|p| p.custom_table(columns));
+                self.custom_table(columns).render(previous.as_ref(), &style)
+            }
+            OutputFormat::JsonPath(template) => template.render(&self.data),
+            OutputFormat::Template(template) => self.items().into_iter()
+                .map(|item| format!("{}\n", template.render(item)))
+                .collect(),
+        })
+    }
+
+    pub fn print(&self, options: &OutputOptions) -> Result<()> {
+        print!("{}", self.render(options, None)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_custom_columns() {
+        let columns = parse_columns("NAME:.config.name, VOL:.config.volume.percent").unwrap();
+        let headers: Vec<&str> = columns.iter().map(|(header, _)| header.as_str()).collect();
+        assert_eq!(headers, ["NAME", "VOL"]);
+
+        let client = json!({ "config": { "name": "Kitchen", "volume": { "percent": 40 } } });
+        assert_eq!(columns[0].1.select_text(&client, ","), Some("Kitchen".to_string()));
+        assert_eq!(columns[1].1.select_text(&client, ","), Some("40".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_custom_columns() {
+        assert!(parse_columns("NAME").is_err());
+        assert!(parse_columns("NAME:config.name").is_err());
+        assert!(OutputFormat::parse("custom-columns=NAME:.id,VOL").is_err());
+    }
+
+    #[test]
+    fn parses_output_formats() {
+        assert_eq!(OutputFormat::parse("wide").unwrap(), OutputFormat::Wide);
+        assert!(matches!(OutputFormat::parse("custom-columns=ID:.id").unwrap(), OutputFormat::CustomColumns(_)));
+        assert!(OutputFormat::parse("json=x").is_err());
+        assert!(OutputFormat::parse("jsonpath").is_err());
+        assert!(OutputFormat::parse("xml").is_err());
+    }
+}